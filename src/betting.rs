@@ -0,0 +1,530 @@
+//! # betting
+//!
+//! `betting` runs one round of betting under a no limit, pot limit or fixed limit structure.  It checks each
+//! action, works out the smallest and largest bet or raise, keeps track of what each player has put in and
+//! decides who acts next.
+//!
+//! A raise must be at least as big as the last full bet or raise on the round.  A player who goes all in for
+//! less than that doesn't reopen the betting, so the players who had already acted may only call or fold.
+//! Several short all ins reopen it for a player once together they raise the bet that player last acted on by
+//! at least a full raise.
+//!
+//! ```
+//! use poker::betting::{Action, Legal, Limit, Round};
+//!
+//! // Blinds of 5 and 10, and the third player has 17 chips.
+//! let mut round = Round::new(Limit::NoLimit, 10, 0, &[100, 100, 17], &[false, false, false]);
+//! round.post(0, 5);
+//! round.post(1, 10);
+//! round.start(1);
+//!
+//! round.apply(Action::AllIn).unwrap();
+//! // The raise to 17 is less than a full raise, so the next raise must still be to at least 27.
+//! assert_eq!(round.legal_actions(), vec![Legal::Fold, Legal::Call(12), Legal::Raise{ min: 27, max: 100 }]);
+//! ```
+
+use std::fmt;
+
+/// How much a player may bet or raise.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum Limit {
+    /// Any amount up to the player's whole stack.
+    NoLimit,
+    /// Up to the size of the pot after calling.
+    PotLimit,
+    /// Every bet and raise is one bet, and a round has at most `max_bets` bets including the opening bet or
+    /// the big blind.
+    FixedLimit{ max_bets: usize }
+}
+
+/// Something the player to act does.  The amount of a bet or a raise is the player's total bet on this round.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum Action {
+    Fold,
+    Check,
+    Call,
+    Bet(u64),
+    Raise(u64),
+    /// Put in every chip, which is a call, a bet or a raise depending on the bet to call.
+    AllIn
+}
+
+impl fmt::Display for Action {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Action::Fold => write!(f, "fold"),
+            Action::Check => write!(f, "check"),
+            Action::Call => write!(f, "call"),
+            Action::Bet(amount) => write!(f, "bet {}", amount),
+            Action::Raise(amount) => write!(f, "raise to {}", amount),
+            Action::AllIn => write!(f, "go all in")
+        }
+    }
+}
+
+/// An action the player to act may take.  Bets and raises may be any total from `min` to `max`, and a `min`
+/// below the usual size means the player only has enough to go all in.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum Legal {
+    Fold,
+    Check,
+    /// Calling costs this many chips, less than the bet if it puts the player all in.
+    Call(u64),
+    Bet{ min: u64, max: u64 },
+    Raise{ min: u64, max: u64 }
+}
+
+impl Legal {
+    /// True if the action is one of the ones this allows.
+    pub fn allows(&self, action: &Action) -> bool {
+        match (self, action) {
+            (Legal::Fold, Action::Fold) | (Legal::Check, Action::Check) | (Legal::Call(_), Action::Call) => true,
+            (Legal::Bet{ min, max }, Action::Bet(amount)) | (Legal::Raise{ min, max }, Action::Raise(amount)) => {
+                min <= amount && amount <= max
+            },
+            _ => false
+        }
+    }
+}
+
+/// One round of betting, such as the flop.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Round {
+    limit: Limit,
+    // The big blind, or the size of one bet in fixed limit.
+    bet_size: u64,
+    // The chips from earlier rounds.
+    pot: u64,
+    stacks: Vec<u64>,
+    bets: Vec<u64>,
+    folded: Vec<bool>,
+    // The total bet that everyone must match, and the smallest raise on top of it.
+    current_bet: u64,
+    min_raise: u64,
+    bets_made: usize,
+    acted: Vec<bool>,
+    // False for a player who has acted since the last full raise.
+    can_raise: Vec<bool>,
+    // The bet each player last matched or made when they acted.
+    faced: Vec<u64>,
+    to_act: Option<usize>
+}
+
+impl Round {
+    /// A round between players with these stacks, after `pot` chips from earlier rounds.  Folded players take
+    /// no part.  The `bet_size` is the big blind, or the size of one bet in fixed limit.
+    pub fn new(limit: Limit, bet_size: u64, pot: u64, stacks: &[u64], folded: &[bool]) -> Round {
+        Round {
+            limit,
+            bet_size,
+            pot,
+            stacks: stacks.to_vec(),
+            bets: vec![0; stacks.len()],
+            folded: folded.to_vec(),
+            current_bet: 0,
+            min_raise: bet_size,
+            bets_made: 0,
+            acted: vec![false; stacks.len()],
+            can_raise: vec![true; stacks.len()],
+            faced: vec![0; stacks.len()],
+            to_act: None
+        }
+    }
+
+    /// Post a forced bet such as a blind.  The player doesn't count as having acted, and everyone else must
+    /// match the full amount even if the player is all in for less.
+    pub fn post(&mut self, seat: usize, amount: u64) {
+        self.commit(seat, amount);
+        if amount > self.current_bet {
+            self.current_bet = amount;
+            if amount >= self.bet_size {
+                self.bets_made = 1;
+            }
+        }
+    }
+
+    /// Post a stud bring-in, which may be less than a full bet.  The next players may call it or complete it to a
+    /// full bet, and completing counts as the first bet of the round.
+    ///
+    /// Unlike a blind, a player all in for less than the bring-in only has to be called for what they put in.
+    pub fn post_bring_in(&mut self, seat: usize, amount: u64) {
+        self.commit(seat, amount);
+        self.current_bet = self.current_bet.max(self.bets[seat]);
+        if self.current_bet >= self.bet_size {
+            self.bets_made = 1;
+        } else if self.current_bet > 0 {
+            self.min_raise = self.bet_size - self.current_bet;
+        }
+    }
+
+    /// Start the action with the first player after `after` who needs to act.
+    pub fn start(&mut self, after: usize) {
+        self.to_act = self.next_to_act(after);
+    }
+
+    /// The seat of the player to act, or `None` once the round is over.
+    pub fn to_act(&self) -> Option<usize> {
+        self.to_act
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.to_act.is_none()
+    }
+
+    pub fn stacks(&self) -> &[u64] {
+        &self.stacks
+    }
+
+    /// The chips each player has put in on this round.
+    pub fn bets(&self) -> &[u64] {
+        &self.bets
+    }
+
+    pub fn folded(&self) -> &[bool] {
+        &self.folded
+    }
+
+    /// The total bet that a player must match to stay in the hand.
+    pub fn current_bet(&self) -> u64 {
+        self.current_bet
+    }
+
+    /// The smallest amount a raise must add to the current bet, unless the player is all in.
+    pub fn min_raise(&self) -> u64 {
+        self.min_raise
+    }
+
+    /// All the chips in the middle, including bets on this round.
+    pub fn pot(&self) -> u64 {
+        self.pot + self.bets.iter().sum::<u64>()
+    }
+
+    /// The actions the player to act may take, empty once the round is over.
+    pub fn legal_actions(&self) -> Vec<Legal> {
+        let seat = match self.to_act {
+            Some(seat) => seat,
+            None => return Vec::new()
+        };
+
+        let to_call = self.current_bet.saturating_sub(self.bets[seat]);
+        let all_in = self.bets[seat] + self.stacks[seat];
+
+        let mut legal = Vec::new();
+        if to_call > 0 {
+            legal.push(Legal::Fold);
+            legal.push(Legal::Call(to_call.min(self.stacks[seat])));
+        } else {
+            legal.push(Legal::Check);
+        }
+
+        // There is no point raising when nobody can call.
+        let capped = match self.limit {
+            Limit::FixedLimit{ max_bets } => self.bets_made >= max_bets,
+            _ => false
+        };
+        if all_in > self.current_bet && self.can_raise[seat] && !capped && self.others_can_act(seat) {
+            let min = self.current_bet + self.min_raise;
+            let max = match self.limit {
+                Limit::NoLimit => all_in,
+                Limit::PotLimit => min.max(self.current_bet + self.pot() + to_call),
+                Limit::FixedLimit{ .. } => min
+            };
+
+            let (min, max) = (min.min(all_in), max.min(all_in));
+            if self.current_bet == 0 {
+                legal.push(Legal::Bet{ min, max });
+            } else {
+                legal.push(Legal::Raise{ min, max });
+            }
+        }
+
+        legal
+    }
+
+    /// Take an action for the player to act and move on to the next player.
+    pub fn apply(&mut self, action: Action) -> Result<(), String> {
+        let seat = self.to_act.ok_or_else(|| String::from("The betting round is over."))?;
+        let all_in = self.bets[seat] + self.stacks[seat];
+        let action = match action {
+            Action::AllIn if all_in <= self.current_bet => Action::Call,
+            Action::AllIn if self.current_bet == 0 => Action::Bet(all_in),
+            Action::AllIn => Action::Raise(all_in),
+            other => other
+        };
+
+        if !self.legal_actions().iter().any(|legal| legal.allows(&action)) {
+            return Err(format!("Seat {} can't {}.", seat, action));
+        }
+
+        match action {
+            Action::Fold => self.folded[seat] = true,
+            Action::Check => (),
+            Action::AllIn => unreachable!("An all in was turned in to a bet, call or raise above."),
+            Action::Call => self.commit(seat, self.current_bet - self.bets[seat]),
+            Action::Bet(amount) | Action::Raise(amount) => {
+                self.commit(seat, amount - self.bets[seat]);
+                let raise = amount - self.current_bet;
+                if raise >= self.min_raise {
+                    // A fixed limit raise is always one bet, even after completing a bring-in.
+                    self.min_raise = match self.limit {
+                        Limit::FixedLimit{ .. } => self.bet_size,
+                        _ => raise
+                    };
+                    self.bets_made += 1;
+                    self.acted = vec![false; self.acted.len()];
+                    self.can_raise = vec![true; self.can_raise.len()];
+                } else {
+                    // An all in for less than a full raise only reopens the betting for players yet to act, and
+                    // for players who have acted once the short all ins since then add up to a full raise.
+                    for other in 0..self.can_raise.len() {
+                        if self.acted[other] {
+                            self.can_raise[other] = amount - self.faced[other] >= self.min_raise;
+                        }
+                    }
+                }
+                self.current_bet = amount;
+            }
+        }
+
+        self.acted[seat] = true;
+        self.faced[seat] = self.current_bet;
+        self.to_act = match self.folded.iter().filter(|folded| !**folded).count() {
+            1 => None,
+            _ => self.next_to_act(seat)
+        };
+        Ok(())
+    }
+
+    /// Give back the part of the biggest bet that nobody called, returning the seat and the chips.
+    pub fn return_uncalled(&mut self) -> Option<(usize, u64)> {
+        let mut bets = self.bets.clone();
+        bets.sort_unstable();
+        match bets[..] {
+            [.., second, highest] if highest > second => {
+                let seat = self.bets.iter().position(|bet| *bet == highest).unwrap();
+                self.bets[seat] -= highest - second;
+                self.stacks[seat] += highest - second;
+                Some((seat, highest - second))
+            },
+            _ => None
+        }
+    }
+
+    /// Move chips from a player's stack to their bet, all in if they don't have enough.
+    fn commit(&mut self, seat: usize, amount: u64) {
+        let amount = amount.min(self.stacks[seat]);
+        self.stacks[seat] -= amount;
+        self.bets[seat] += amount;
+    }
+
+    fn can_act(&self, seat: usize) -> bool {
+        !self.folded[seat] && self.stacks[seat] > 0
+    }
+
+    fn others_can_act(&self, seat: usize) -> bool {
+        (0..self.stacks.len()).any(|other| other != seat && self.can_act(other))
+    }
+
+    /// The next player after `from` who still has to act on this round.
+    fn next_to_act(&self, from: usize) -> Option<usize> {
+        let count = self.stacks.len();
+        (1..=count).map(|offset| (from + offset) % count).find(|seat| {
+            self.can_act(*seat) && (self.bets[*seat] < self.current_bet || (!self.acted[*seat] && self.others_can_act(*seat)))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::table::testing::play;
+
+    /// A round with blinds of 5 and 10 posted by the first two seats and the action on the third.
+    fn preflop(limit: Limit, stacks: &[u64]) -> Round {
+        let mut round = Round::new(limit, 10, 0, stacks, &vec![false; stacks.len()]);
+        round.post(0, 5);
+        round.post(1, 10);
+        round.start(1);
+        round
+    }
+
+    #[test]
+    fn no_limit_min_raise_follows_the_last_raise() {
+        let mut round = preflop(Limit::NoLimit, &[1000, 1000, 1000]);
+        assert_eq!(round.legal_actions(), vec![Legal::Fold, Legal::Call(10), Legal::Raise{ min: 20, max: 1000 }]);
+
+        // A raise of 25 more means the next raise must be at least 25 more again.
+        play(&mut round, &[Action::Raise(35)]);
+        assert_eq!(round.min_raise(), 25);
+        assert_eq!(round.legal_actions(), vec![Legal::Fold, Legal::Call(30), Legal::Raise{ min: 60, max: 1000 }]);
+        assert_eq!(round.apply(Action::Raise(59)), Err(String::from("Seat 0 can't raise to 59.")));
+        play(&mut round, &[Action::Raise(100)]);
+        assert_eq!(round.legal_actions(), vec![Legal::Fold, Legal::Call(90), Legal::Raise{ min: 165, max: 1000 }]);
+    }
+
+    #[test]
+    fn first_bet_is_at_least_the_big_blind() {
+        let mut round = Round::new(Limit::NoLimit, 10, 30, &[100, 5], &[false, false]);
+        round.start(1);
+        assert_eq!(round.to_act(), Some(0));
+        assert_eq!(round.legal_actions(), vec![Legal::Check, Legal::Bet{ min: 10, max: 100 }]);
+        play(&mut round, &[Action::Check]);
+        // Only enough for a short all in bet.
+        assert_eq!(round.legal_actions(), vec![Legal::Check, Legal::Bet{ min: 5, max: 5 }]);
+        play(&mut round, &[Action::AllIn]);
+        assert_eq!(round.legal_actions(), vec![Legal::Fold, Legal::Call(5)]);
+    }
+
+    #[test]
+    fn incomplete_all_in_does_not_reopen_the_betting() {
+        let mut round = preflop(Limit::NoLimit, &[1000, 1000, 1000, 45]);
+        // Seat 2 raises to 30 and seat 3 is all in for 45, 15 more and less than a full raise of 20.
+        play(&mut round, &[Action::Raise(30), Action::AllIn]);
+        assert_eq!(round.current_bet(), 45);
+        assert_eq!(round.min_raise(), 20);
+
+        // The blinds haven't acted since the raise to 30 so they may still raise.
+        assert_eq!(round.legal_actions(), vec![Legal::Fold, Legal::Call(40), Legal::Raise{ min: 65, max: 1000 }]);
+        play(&mut round, &[Action::Call, Action::Call]);
+
+        // Seat 2 already acted, so may only call or fold.
+        assert_eq!(round.to_act(), Some(2));
+        assert_eq!(round.legal_actions(), vec![Legal::Fold, Legal::Call(15)]);
+        assert_eq!(round.apply(Action::Raise(65)), Err(String::from("Seat 2 can't raise to 65.")));
+        play(&mut round, &[Action::Call]);
+        assert!(round.is_complete());
+        assert_eq!(round.pot(), 180);
+    }
+
+    #[test]
+    fn short_all_ins_add_up_to_a_full_raise() {
+        let mut round = Round::new(Limit::NoLimit, 10, 0, &[1000, 35, 1000, 50], &[false; 4]);
+        round.start(3);
+        // Seat 0 bets 20, then seats 1 and 3 are all in for 15 more each.
+        play(&mut round, &[Action::Bet(20), Action::AllIn, Action::Call, Action::AllIn]);
+        assert_eq!(round.current_bet(), 50);
+        assert_eq!(round.min_raise(), 20);
+
+        // Seat 0 acted on 20 and now faces 30 more, a full raise, so may raise again.
+        assert_eq!(round.to_act(), Some(0));
+        assert_eq!(round.legal_actions(), vec![Legal::Fold, Legal::Call(30), Legal::Raise{ min: 70, max: 1000 }]);
+        play(&mut round, &[Action::Call]);
+
+        // Seat 2 acted on 35 and only faces 15 more.
+        assert_eq!(round.to_act(), Some(2));
+        assert_eq!(round.legal_actions(), vec![Legal::Fold, Legal::Call(15)]);
+    }
+
+    #[test]
+    fn full_raise_reopens_the_betting() {
+        let mut round = preflop(Limit::NoLimit, &[1000, 1000, 1000, 45]);
+        play(&mut round, &[Action::Raise(30), Action::AllIn, Action::Raise(100), Action::Fold]);
+        assert_eq!(round.to_act(), Some(2));
+        assert_eq!(round.legal_actions(), vec![Legal::Fold, Legal::Call(70), Legal::Raise{ min: 155, max: 1000 }]);
+    }
+
+    #[test]
+    fn pot_limit() {
+        let mut round = preflop(Limit::PotLimit, &[1000, 1000, 1000]);
+        // Calling 10 makes the pot 25, so the biggest raise is to 35.
+        assert_eq!(round.legal_actions(), vec![Legal::Fold, Legal::Call(10), Legal::Raise{ min: 20, max: 35 }]);
+        assert_eq!(round.apply(Action::AllIn), Err(String::from("Seat 2 can't raise to 1000.")));
+        play(&mut round, &[Action::Raise(35)]);
+        // The small blind calls 30 to make the pot 80, so may raise to 115.
+        assert_eq!(round.legal_actions(), vec![Legal::Fold, Legal::Call(30), Legal::Raise{ min: 60, max: 115 }]);
+
+        let mut round = Round::new(Limit::PotLimit, 10, 80, &[1000, 50], &[false, false]);
+        round.start(1);
+        assert_eq!(round.legal_actions(), vec![Legal::Check, Legal::Bet{ min: 10, max: 80 }]);
+
+        // With nothing in the pot the smallest bet is still allowed.
+        let mut round = Round::new(Limit::PotLimit, 10, 0, &[100, 100], &[false; 2]);
+        round.start(0);
+        assert_eq!(round.legal_actions(), vec![Legal::Check, Legal::Bet{ min: 10, max: 10 }]);
+        play(&mut round, &[Action::Bet(10)]);
+        assert_eq!(round.legal_actions(), vec![Legal::Fold, Legal::Call(10), Legal::Raise{ min: 20, max: 30 }]);
+    }
+
+    #[test]
+    fn fixed_limit_sizes_and_cap() {
+        let mut round = preflop(Limit::FixedLimit{ max_bets: 4 }, &[1000, 1000, 1000]);
+        assert_eq!(round.legal_actions(), vec![Legal::Fold, Legal::Call(10), Legal::Raise{ min: 20, max: 20 }]);
+        play(&mut round, &[Action::Raise(20), Action::Raise(30), Action::Raise(40)]);
+        // The big blind and three raises are the cap.
+        assert_eq!(round.legal_actions(), vec![Legal::Fold, Legal::Call(20)]);
+
+        let mut round = Round::new(Limit::FixedLimit{ max_bets: 4 }, 20, 60, &[1000, 1000], &[false, false]);
+        round.start(1);
+        assert_eq!(round.legal_actions(), vec![Legal::Check, Legal::Bet{ min: 20, max: 20 }]);
+        assert_eq!(round.apply(Action::Bet(40)), Err(String::from("Seat 0 can't bet 40.")));
+    }
+
+    #[test]
+    fn bring_in_and_completion() {
+        let mut round = Round::new(Limit::FixedLimit{ max_bets: 4 }, 10, 3, &[100, 100, 100], &[false; 3]);
+        round.post_bring_in(1, 3);
+        round.start(1);
+        assert_eq!(round.to_act(), Some(2));
+        assert_eq!(round.legal_actions(), vec![Legal::Fold, Legal::Call(3), Legal::Raise{ min: 10, max: 10 }]);
+
+        // Completing to 10 is the first bet, so three more raises of 10 are allowed.
+        play(&mut round, &[Action::Raise(10)]);
+        assert_eq!(round.legal_actions(), vec![Legal::Fold, Legal::Call(10), Legal::Raise{ min: 20, max: 20 }]);
+        play(&mut round, &[Action::Raise(20), Action::Raise(30), Action::Raise(40)]);
+        assert_eq!(round.legal_actions(), vec![Legal::Fold, Legal::Call(20)]);
+    }
+
+    #[test]
+    fn short_bring_in() {
+        // The bring-in is 3 but the player only has 1 chip.
+        let mut round = Round::new(Limit::FixedLimit{ max_bets: 4 }, 10, 0, &[1, 100, 100], &[false; 3]);
+        round.post_bring_in(0, 3);
+        round.start(0);
+        assert_eq!(round.current_bet(), 1);
+        assert_eq!(round.legal_actions(), vec![Legal::Fold, Legal::Call(1), Legal::Raise{ min: 10, max: 10 }]);
+
+        // With nothing to post there is no bet to call.
+        let mut round = Round::new(Limit::FixedLimit{ max_bets: 4 }, 10, 0, &[0, 100, 100], &[false; 3]);
+        round.post_bring_in(0, 3);
+        round.start(0);
+        assert_eq!(round.current_bet(), 0);
+        assert_eq!(round.legal_actions(), vec![Legal::Check, Legal::Bet{ min: 10, max: 10 }]);
+    }
+
+    #[test]
+    fn bring_in_may_complete_when_called() {
+        let mut round = Round::new(Limit::FixedLimit{ max_bets: 4 }, 10, 0, &[100, 100], &[false; 2]);
+        round.post_bring_in(0, 3);
+        round.start(0);
+        play(&mut round, &[Action::Call]);
+        assert_eq!(round.to_act(), Some(0));
+        assert_eq!(round.legal_actions(), vec![Legal::Check, Legal::Raise{ min: 10, max: 10 }]);
+    }
+
+    #[test]
+    fn all_in_call_and_uncalled_bet() {
+        let mut round = preflop(Limit::NoLimit, &[1000, 1000, 60]);
+        play(&mut round, &[Action::Fold, Action::Fold]);
+        assert!(round.is_complete());
+        assert_eq!(round.folded(), &[true, false, true]);
+
+        let mut round = preflop(Limit::NoLimit, &[1000, 1000, 60]);
+        play(&mut round, &[Action::Call, Action::Fold, Action::Raise(500), Action::AllIn]);
+        assert!(round.is_complete());
+        assert_eq!(round.bets(), &[5, 500, 60]);
+        assert_eq!(round.return_uncalled(), Some((1, 440)));
+        assert_eq!(round.bets(), &[5, 60, 60]);
+        assert_eq!(round.stacks(), &[995, 940, 0]);
+        assert_eq!(round.return_uncalled(), None);
+    }
+
+    #[test]
+    fn round_over() {
+        let mut round = preflop(Limit::NoLimit, &[1000, 1000]);
+        // Heads up with the first seat as the small blind, whose turn it is.
+        assert_eq!(round.to_act(), Some(0));
+        play(&mut round, &[Action::Call, Action::Check]);
+        assert_eq!(round.legal_actions(), Vec::new());
+        assert_eq!(round.apply(Action::Check), Err(String::from("The betting round is over.")));
+    }
+}
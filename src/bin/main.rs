@@ -6,7 +6,8 @@ use std::io;
 use std::io::stdin;
 use std::io::BufRead;
 
-use std::cmp::Ordering;
+/// Hands on a line are named by a letter, so there can be at most 26.
+const MAX_HANDS: usize = 26;
 
 #[derive(Debug)]
 enum ParseError {
@@ -15,8 +16,10 @@ enum ParseError {
     FewLines,
     MissingLeftHand,
     MissingRightHand,
+    TooManyHands(usize),
     LeftHandInvalid(usize, String),
-    RightHandInvalid(usize, String)
+    RightHandInvalid(usize, String),
+    HandInvalid(usize, usize, String)
 }
 
 impl From<io::Error> for ParseError {
@@ -25,7 +28,7 @@ impl From<io::Error> for ParseError {
     }
 }
 
-fn parse(reader:&mut dyn BufRead) -> Result<Vec<(PokerHand, PokerHand)>, ParseError> {
+fn parse(reader:&mut dyn BufRead) -> Result<Vec<Vec<PokerHand>>, ParseError> {
     let mut buf = String::new();
 
     reader.read_line(&mut buf)?;
@@ -43,28 +46,29 @@ fn parse(reader:&mut dyn BufRead) -> Result<Vec<(PokerHand, PokerHand)>, ParseEr
             return Err(ParseError::FewLines)
         }
 
-        let mut parts = buf.split_ascii_whitespace();
-        let left_hand_str = match parts.next() {
-            Some(x) => x,
-            None => return Err(ParseError::MissingLeftHand)
-        };
-
-        let right_hand_str = match parts.next() {
-            Some(x) => x,
-            None => return Err(ParseError::MissingRightHand)
-        };
-        
-        let left_hand = match PokerHand::from_str(left_hand_str) {
-            Ok(x) => x,
-            Err(err) => return Err(ParseError::LeftHandInvalid(line_idx + 1, err))
-        };
-
-        let right_hand = match PokerHand::from_str(right_hand_str) {
-            Ok(x) => x,
-            Err(err) => return Err(ParseError::RightHandInvalid(line_idx + 1, err))
-        };
-
-        result.push((left_hand, right_hand));
+        let hand_strs: Vec<&str> = buf.split_ascii_whitespace().collect();
+        match hand_strs.len() {
+            0 => return Err(ParseError::MissingLeftHand),
+            1 => return Err(ParseError::MissingRightHand),
+            count if count > MAX_HANDS => return Err(ParseError::TooManyHands(line_idx + 1)),
+            _ => ()
+        }
+
+        let mut hands = Vec::new();
+        for (hand_idx, hand_str) in hand_strs.iter().enumerate() {
+            let hand = match PokerHand::from_str(hand_str) {
+                Ok(x) => x,
+                Err(err) => return Err(match hand_idx {
+                    0 => ParseError::LeftHandInvalid(line_idx + 1, err),
+                    1 => ParseError::RightHandInvalid(line_idx + 1, err),
+                    _ => ParseError::HandInvalid(line_idx + 1, hand_idx + 1, err)
+                })
+            };
+
+            hands.push(hand);
+        }
+
+        result.push(hands);
     }
     
     Ok(result)
@@ -73,7 +77,10 @@ fn parse(reader:&mut dyn BufRead) -> Result<Vec<(PokerHand, PokerHand)>, ParseEr
 
 fn print_score(score:&Score) {
     match score {
+        Score::FiveOfAKind(_) => print!("FIVEOFAKIND"),
+        Score::StraightFlush(_) => print!("STRAIGHTFLUSH"),
         Score::FullHouse{..} => print!("FULLHOUSE"),
+        Score::Flush{..} => print!("FLUSH"),
         Score::Straight(_) => print!("STRAIGHT"), 
         Score::FourOfAKind{..} => print!("FOUROFAKIND"),
         Score::TwoPair{..} => print!("TWOPAIR"),
@@ -83,12 +90,9 @@ fn print_score(score:&Score) {
     }
 }
 
-fn print_ordering(ordering:Ordering) {
-    match ordering {
-        Ordering::Less => { print!("a"); },
-        Ordering::Equal => { print!("ab"); },
-        Ordering::Greater => { print!("b") }
-    }
+/// The winning hands by letter, "a" for the first hand, "b" for the second and so on.
+fn winner_letters(winners:&[usize]) -> String {
+    winners.iter().map(|idx| (b'a' + *idx as u8) as char).collect()
 }
 
 fn main() {
@@ -102,23 +106,23 @@ fn main() {
                 ParseError::MissingLeftHand => eprintln!("Left hand missing."),
                 ParseError::LeftHandInvalid(l, msg) => eprintln!("The left hand of line {} is invalid: {}", l, msg),
                 ParseError::MissingRightHand => eprintln!("Right hand missing."),
-                ParseError::RightHandInvalid(l, msg) => eprintln!("The right hand of line {} is invalid: {}", l, msg)
+                ParseError::RightHandInvalid(l, msg) => eprintln!("The right hand of line {} is invalid: {}", l, msg),
+                ParseError::TooManyHands(l) => eprintln!("Line {} has more than {} hands.", l, MAX_HANDS),
+                ParseError::HandInvalid(l, h, msg) => eprintln!("Hand {} of line {} is invalid: {}", h, l, msg)
             }
 
             return;
         }
     };
 
-    for (left, right) in hands {
-        let left_score = left.score();
-        let right_score = right.score();
-        let ordering = poker::compare_hands(&left_score, &right_score);
-        
-        print_score(&left_score);
-        print!(" ");
-        print_score(&right_score);
-        print!(" ");
-        print_ordering(ordering);
+    for line in hands {
+        let scores: Vec<Score> = line.iter().map(|hand| hand.score()).collect();
+
+        for score in &scores {
+            print_score(score);
+            print!(" ");
+        }
+        print!("{}", winner_letters(&poker::showdown(&scores)));
         println!();
 
     }
@@ -127,7 +131,7 @@ fn main() {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use poker::cards::Card;
+    use poker::cards::Rank;
     use std::io::BufReader;
 
     #[test]
@@ -137,9 +141,35 @@ mod tests {
         let result = parse(&mut buf).unwrap();
         assert_eq!(result.len(), 1);
 
-        let (left, right) = &result[0];
-        assert_eq!(left.cards(), &[Card::Two, Card::Three, Card::Four, Card::Five, Card::Six]);
-        assert_eq!(right.cards(), &[Card::Three, Card::Four, Card::Five, Card::Six, Card::Seven]);
+        let (left, right) = (&result[0][0], &result[0][1]);
+        assert_eq!(left.ranks(), &[Rank::Two, Rank::Three, Rank::Four, Rank::Five, Rank::Six]);
+        assert_eq!(right.ranks(), &[Rank::Three, Rank::Four, Rank::Five, Rank::Six, Rank::Seven]);
+    }
+
+    #[test]
+    fn test_parse_many_hands() {
+        let example = "1\n23456 34567 AAKKK\n".as_bytes();
+        let mut buf = BufReader::new(example);
+        let result = parse(&mut buf).unwrap();
+        assert_eq!(result[0].len(), 3);
+        assert_eq!(result[0][2].ranks(), &[Rank::Ace, Rank::Ace, Rank::King, Rank::King, Rank::King]);
+    }
+
+    #[test]
+    fn test_parse_third_hand_invalid() {
+        let example = "1\n23456 34567 AAKKX\n".as_bytes();
+        let mut buf = BufReader::new(example);
+        match parse(&mut buf) {
+            Err(ParseError::HandInvalid(1, 3, msg)) => assert_eq!(msg, "Character 'X' is not valid."),
+            other => panic!("Unexpected result {:?}", other)
+        }
+    }
+
+    #[test]
+    fn test_winner_letters() {
+        assert_eq!(winner_letters(&[0]), "a");
+        assert_eq!(winner_letters(&[0, 1]), "ab");
+        assert_eq!(winner_letters(&[1, 3, 4]), "bde");
     }
 
 }
\ No newline at end of file
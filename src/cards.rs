@@ -2,9 +2,14 @@
 //!
 //! `cards` is structures and functions for cards.
 
-/// A card from a standard deck of cards.
-#[derive(Debug, PartialEq, Eq, Clone, Hash, PartialOrd, Ord)]
-pub enum Card {
+use crate::rng::Rng;
+
+use std::fmt;
+use std::str::FromStr;
+
+/// The rank of a card from a standard deck of cards.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash, PartialOrd, Ord)]
+pub enum Rank {
     Ace,
     Two,
     Three,
@@ -20,70 +25,503 @@ pub enum Card {
     King
 }
 
-impl Card {
-    /// Crete a card from a character shorthand.
-    /// 
+impl Rank {
+    /// Every rank, in the same order as the enum.
+    pub const ALL: [Rank; 13] = [
+        Rank::Ace, Rank::Two, Rank::Three, Rank::Four, Rank::Five, Rank::Six, Rank::Seven,
+        Rank::Eight, Rank::Nine, Rank::Ten, Rank::Jack, Rank::Queen, Rank::King
+    ];
+
+    /// Crete a rank from a character shorthand.
+    ///
     /// The numbered cards use their number except for 10 which is 'T'.
-    /// 
+    ///
     /// # Examples
     /// ```
-    /// 
-    /// let two = poker::cards::Card::from_char('2');
-    /// let ten = poker::cards::Card::from_char('T');
-    /// let king = poker::cards::Card::from_char('K');
+    ///
+    /// let two = poker::cards::Rank::from_char('2');
+    /// let ten = poker::cards::Rank::from_char('T');
+    /// let king = poker::cards::Rank::from_char('K');
     /// ```
-    pub fn from_char(c:char) -> Result<Card, String> {
+    pub fn from_char(c:char) -> Result<Rank, String> {
         match c {
-            '2' => Ok(Card::Two),
-            '3' => Ok(Card::Three),
-            '4' => Ok(Card::Four),
-            '5' => Ok(Card::Five),
-            '6' => Ok(Card::Six),
-            '7' => Ok(Card::Seven),
-            '8' => Ok(Card::Eight),
-            '9' => Ok(Card::Nine),
-            'T' => Ok(Card::Ten),
-            'J' => Ok(Card::Jack),
-            'Q' => Ok(Card::Queen),
-            'K' => Ok(Card::King),
-            'A' => Ok(Card::Ace),
+            '2' => Ok(Rank::Two),
+            '3' => Ok(Rank::Three),
+            '4' => Ok(Rank::Four),
+            '5' => Ok(Rank::Five),
+            '6' => Ok(Rank::Six),
+            '7' => Ok(Rank::Seven),
+            '8' => Ok(Rank::Eight),
+            '9' => Ok(Rank::Nine),
+            'T' => Ok(Rank::Ten),
+            'J' => Ok(Rank::Jack),
+            'Q' => Ok(Rank::Queen),
+            'K' => Ok(Rank::King),
+            'A' => Ok(Rank::Ace),
             _ => Err(format!("Character '{}' is not valid.", c))
         }
     }
+
+    /// The character shorthand for the rank, the reverse of `from_char`.
+    pub fn to_char(self) -> char {
+        match self {
+            Rank::Two => '2',
+            Rank::Three => '3',
+            Rank::Four => '4',
+            Rank::Five => '5',
+            Rank::Six => '6',
+            Rank::Seven => '7',
+            Rank::Eight => '8',
+            Rank::Nine => '9',
+            Rank::Ten => 'T',
+            Rank::Jack => 'J',
+            Rank::Queen => 'Q',
+            Rank::King => 'K',
+            Rank::Ace => 'A',
+        }
+    }
+}
+
+impl fmt::Display for Rank {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.to_char())
+    }
+}
+
+/// The suit of a card from a standard deck of cards.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash, PartialOrd, Ord)]
+pub enum Suit {
+    Clubs,
+    Diamonds,
+    Hearts,
+    Spades
+}
+
+impl Suit {
+    /// Every suit, in the same order as the enum.
+    pub const ALL: [Suit; 4] = [Suit::Clubs, Suit::Diamonds, Suit::Hearts, Suit::Spades];
+
+    /// Create a suit from its lower case initial, 'c', 'd', 'h' or 's'.
+    pub fn from_char(c:char) -> Result<Suit, String> {
+        match c {
+            'c' => Ok(Suit::Clubs),
+            'd' => Ok(Suit::Diamonds),
+            'h' => Ok(Suit::Hearts),
+            's' => Ok(Suit::Spades),
+            _ => Err(format!("Suit '{}' is not valid.", c))
+        }
+    }
+
+    /// The character shorthand for the suit, the reverse of `from_char`.
+    pub fn to_char(self) -> char {
+        match self {
+            Suit::Clubs => 'c',
+            Suit::Diamonds => 'd',
+            Suit::Hearts => 'h',
+            Suit::Spades => 's',
+        }
+    }
+}
+
+impl fmt::Display for Suit {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.to_char())
+    }
+}
+
+/// A card from a standard deck of cards.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash, PartialOrd, Ord)]
+pub struct Card {
+    pub rank: Rank,
+    pub suit: Suit
+}
+
+impl Card {
+    pub fn new(rank: Rank, suit: Suit) -> Card {
+        Card { rank, suit }
+    }
+
+    /// Create a card from a rank character and a suit character.
+    ///
+    /// # Examples
+    /// ```
+    /// use poker::cards::{Card, Rank, Suit};
+    ///
+    /// assert_eq!(Card::from_chars('T', 'd'), Ok(Card::new(Rank::Ten, Suit::Diamonds)));
+    /// ```
+    pub fn from_chars(rank:char, suit:char) -> Result<Card, String> {
+        Ok(Card::new(Rank::from_char(rank)?, Suit::from_char(suit)?))
+    }
+
+    /// All 52 cards of a standard deck, grouped by suit.
+    pub fn all() -> Vec<Card> {
+        let mut cards = Vec::with_capacity(52);
+        for suit in Suit::ALL.iter() {
+            for rank in Rank::ALL.iter() {
+                cards.push(Card::new(*rank, *suit));
+            }
+        }
+
+        cards
+    }
+}
+
+/// Parse the two character shorthand for a card, such as "As", "Td" or "7h".
+impl FromStr for Card {
+    type Err = String;
+
+    fn from_str(input: &str) -> Result<Card, String> {
+        let chars:Vec<char> = input.chars().collect();
+        if chars.len() != 2 {
+            return Err(format!("Card '{}' should be a rank and a suit.", input));
+        }
+
+        Card::from_chars(chars[0], chars[1])
+    }
+}
+
+impl fmt::Display for Card {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}{}", self.rank, self.suit)
+    }
+}
+
+/// Parse a list of distinct cards such as "AhKh" or "2h 7h 9c".
+///
+/// # Examples
+/// ```
+/// use poker::cards::{parse_cards, Card, Rank, Suit};
+///
+/// let cards = parse_cards("AhKh").unwrap();
+/// assert_eq!(cards, vec![Card::new(Rank::Ace, Suit::Hearts), Card::new(Rank::King, Suit::Hearts)]);
+/// ```
+pub fn parse_cards(input:&str) -> Result<Vec<Card>, String> {
+    let chars:Vec<char> = input.chars().filter(|c| !c.is_whitespace()).collect();
+    if !chars.len().is_multiple_of(2) {
+        return Err(format!("Cards '{}' should be pairs of a rank and a suit.", input));
+    }
+
+    let mut cards: Vec<Card> = Vec::new();
+    for pair in chars.chunks(2) {
+        let card = Card::from_chars(pair[0], pair[1])?;
+        if cards.contains(&card) {
+            return Err(format!("Card '{}' appears more than once.", card));
+        }
+        cards.push(card);
+    }
+
+    Ok(cards)
+}
+
+/// A card dealt from a deck that may include jokers.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash, PartialOrd, Ord)]
+pub enum DeckCard {
+    Card(Card),
+    Joker
+}
+
+/// Parse a card such as "As", or "Jk" for a joker.
+impl FromStr for DeckCard {
+    type Err = String;
+
+    fn from_str(input: &str) -> Result<DeckCard, String> {
+        match input {
+            "Jk" => Ok(DeckCard::Joker),
+            _ => Ok(DeckCard::Card(input.parse()?))
+        }
+    }
 }
 
+impl fmt::Display for DeckCard {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DeckCard::Card(card) => write!(f, "{}", card),
+            DeckCard::Joker => write!(f, "Jk")
+        }
+    }
+}
 
+/// A deck of cards to shuffle and deal from.
+///
+/// A standard deck holds `Card`s and a deck with jokers holds `DeckCard`s.  Cards are dealt from the top of the
+/// deck, which is the end of `remaining`.
+///
+/// # Examples
+/// ```
+/// use poker::cards::Deck;
+/// use poker::rng::SplitMix64;
+///
+/// let mut deck = Deck::new();
+/// deck.shuffle(&mut SplitMix64::new(2019));
+/// let hands = deck.deal_hands(2, 2).unwrap();
+/// let board = deck.deal_many(5).unwrap();
+/// assert_eq!(deck.len(), 52 - 4 - 5);
+/// ```
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Deck<T = Card> {
+    cards: Vec<T>
+}
+
+impl Deck<Card> {
+    /// A standard 52 card deck in the order of `Card::all`.
+    pub fn new() -> Deck<Card> {
+        Deck { cards: Card::all() }
+    }
+}
+
+impl Default for Deck<Card> {
+    fn default() -> Deck<Card> {
+        Deck::new()
+    }
+}
+
+impl Deck<DeckCard> {
+    /// A standard 52 card deck with some jokers on top.
+    pub fn with_jokers(jokers: usize) -> Deck<DeckCard> {
+        let mut cards: Vec<DeckCard> = Card::all().into_iter().map(DeckCard::Card).collect();
+        cards.extend(std::iter::repeat_n(DeckCard::Joker, jokers));
+        Deck { cards }
+    }
+}
+
+impl<T: Copy + PartialEq + fmt::Display> Deck<T> {
+    /// A deck made of any cards, the last card is on top.
+    pub fn from_cards(cards: Vec<T>) -> Deck<T> {
+        Deck { cards }
+    }
+
+    /// Shuffle the whole deck.  The same seed gives the same order every time.
+    pub fn shuffle<R: Rng + ?Sized>(&mut self, rng: &mut R) {
+        // Fisher-Yates
+        for i in (1..self.cards.len()).rev() {
+            let j = rng.below(i + 1);
+            self.cards.swap(i, j);
+        }
+    }
+
+    /// Deal the top card, or `None` if the deck is empty.
+    pub fn deal(&mut self) -> Option<T> {
+        self.cards.pop()
+    }
+
+    /// Deal `count` cards from the top, such as a board.
+    pub fn deal_many(&mut self, count: usize) -> Result<Vec<T>, String> {
+        if count > self.cards.len() {
+            return Err(format!("Required {} cards but only {} remain.", count, self.cards.len()));
+        }
+
+        let mut cards = self.cards.split_off(self.cards.len() - count);
+        cards.reverse();
+        Ok(cards)
+    }
+
+    /// Deal `cards_each` cards to `players` hands, one card at a time to each player in turn.
+    pub fn deal_hands(&mut self, players: usize, cards_each: usize) -> Result<Vec<Vec<T>>, String> {
+        let mut dealt = self.deal_many(players * cards_each)?.into_iter();
+        let mut hands: Vec<Vec<T>> = vec![Vec::with_capacity(cards_each); players];
+        for _ in 0..cards_each {
+            for hand in hands.iter_mut() {
+                hand.push(dealt.next().unwrap());
+            }
+        }
+
+        Ok(hands)
+    }
+
+    /// Take known cards out of the deck, such as cards already dealt or seen.  The deck is left unchanged if
+    /// any card isn't in it.
+    pub fn remove(&mut self, dead: &[T]) -> Result<(), String> {
+        // Find every card first, a card listed twice must be in the deck twice.
+        let mut found: Vec<usize> = Vec::with_capacity(dead.len());
+        for card in dead {
+            match (0..self.cards.len()).find(|idx| self.cards[*idx] == *card && !found.contains(idx)) {
+                Some(idx) => found.push(idx),
+                None => return Err(format!("Card '{}' is not in the deck.", card))
+            }
+        }
+
+        found.sort_unstable();
+        for idx in found.into_iter().rev() {
+            self.cards.remove(idx);
+        }
+
+        Ok(())
+    }
+
+    /// The cards left in the deck, the next card to be dealt is last.
+    pub fn remaining(&self) -> &[T] {
+        &self.cards
+    }
+
+    pub fn len(&self) -> usize {
+        self.cards.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cards.is_empty()
+    }
+}
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::rng::SplitMix64;
 
     #[test]
     fn card_from_char_err() {
-        assert_eq!(Card::from_char('$'), Result::Err(String::from("Character '$' is not valid.")));
+        assert_eq!(Rank::from_char('$'), Result::Err(String::from("Character '$' is not valid.")));
     }
 
     #[test]
     fn card_from_char_ok() {
         let rules = [
-            ('2', Card::Two),
-            ('3', Card::Three),
-            ('4', Card::Four),
-            ('5', Card::Five),
-            ('6', Card::Six),
-            ('7', Card::Seven),
-            ('8', Card::Eight),
-            ('9', Card::Nine),
-            ('T', Card::Ten),
-            ('J', Card::Jack),
-            ('Q', Card::Queen),
-            ('K', Card::King),
-            ('A', Card::Ace),
+            ('2', Rank::Two),
+            ('3', Rank::Three),
+            ('4', Rank::Four),
+            ('5', Rank::Five),
+            ('6', Rank::Six),
+            ('7', Rank::Seven),
+            ('8', Rank::Eight),
+            ('9', Rank::Nine),
+            ('T', Rank::Ten),
+            ('J', Rank::Jack),
+            ('Q', Rank::Queen),
+            ('K', Rank::King),
+            ('A', Rank::Ace),
 
         ];
 
         for (character, card) in rules.iter() {
-            assert_eq!(Card::from_char(*character), Result::Ok(card.clone()));
+            assert_eq!(Rank::from_char(*character), Result::Ok(*card));
+            assert_eq!(card.to_char(), *character);
         }
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn card_from_str_ok() {
+        assert_eq!("As".parse::<Card>(), Ok(Card::new(Rank::Ace, Suit::Spades)));
+        assert_eq!("Td".parse::<Card>(), Ok(Card::new(Rank::Ten, Suit::Diamonds)));
+        assert_eq!("7h".parse::<Card>(), Ok(Card::new(Rank::Seven, Suit::Hearts)));
+        assert_eq!("2c".parse::<Card>(), Ok(Card::new(Rank::Two, Suit::Clubs)));
+    }
+
+    #[test]
+    fn card_from_str_err() {
+        assert_eq!("Ax".parse::<Card>(), Err(String::from("Suit 'x' is not valid.")));
+        assert_eq!("A".parse::<Card>(), Err(String::from("Card 'A' should be a rank and a suit.")));
+        assert_eq!("Asd".parse::<Card>(), Err(String::from("Card 'Asd' should be a rank and a suit.")));
+    }
+
+    #[test]
+    fn parse_cards_ok() {
+        assert_eq!(parse_cards("2h 7h9c"), Ok(vec![
+            Card::new(Rank::Two, Suit::Hearts),
+            Card::new(Rank::Seven, Suit::Hearts),
+            Card::new(Rank::Nine, Suit::Clubs)]));
+        assert_eq!(parse_cards(""), Ok(vec![]));
+    }
+
+    #[test]
+    fn parse_cards_err() {
+        assert_eq!(parse_cards("AhK"), Err(String::from("Cards 'AhK' should be pairs of a rank and a suit.")));
+        assert_eq!(parse_cards("AhAh"), Err(String::from("Card 'Ah' appears more than once.")));
+    }
+
+    #[test]
+    fn card_display_round_trip() {
+        for card in Card::all() {
+            assert_eq!(card.to_string().parse::<Card>(), Ok(card));
+        }
+    }
+
+    #[test]
+    fn deck_has_52_cards() {
+        let deck = Deck::new();
+        assert_eq!(deck.len(), 52);
+        assert_eq!(deck.remaining(), &Card::all()[..]);
+    }
+
+    #[test]
+    fn shuffle_is_reproducible() {
+        let mut first = Deck::new();
+        let mut second = Deck::new();
+        first.shuffle(&mut SplitMix64::new(99));
+        second.shuffle(&mut SplitMix64::new(99));
+        assert_eq!(first, second);
+        assert_ne!(first, Deck::new());
+
+        let mut third = Deck::new();
+        third.shuffle(&mut SplitMix64::new(100));
+        assert_ne!(first, third);
+    }
+
+    #[test]
+    fn shuffle_keeps_every_card() {
+        let mut deck = Deck::new();
+        deck.shuffle(&mut SplitMix64::new(1));
+        let mut cards = deck.remaining().to_vec();
+        let mut all = Card::all();
+        cards.sort();
+        all.sort();
+        assert_eq!(cards, all);
+    }
+
+    #[test]
+    fn deal_from_the_top() {
+        let mut deck = Deck::new();
+        assert_eq!(deck.deal(), Some(Card::new(Rank::King, Suit::Spades)));
+        assert_eq!(deck.deal_many(2), Ok(vec![Card::new(Rank::Queen, Suit::Spades), Card::new(Rank::Jack, Suit::Spades)]));
+        assert_eq!(deck.len(), 49);
+        assert_eq!(deck.deal_many(50), Err(String::from("Required 50 cards but only 49 remain.")));
+        assert_eq!(deck.len(), 49);
+    }
+
+    #[test]
+    fn deal_hands_one_card_at_a_time() {
+        let mut deck = Deck::new();
+        let hands = deck.deal_hands(2, 2).unwrap();
+        assert_eq!(hands, vec![parse_cards("KsJs").unwrap(), parse_cards("QsTs").unwrap()]);
+        assert_eq!(deck.len(), 48);
+    }
+
+    #[test]
+    fn remove_dead_cards() {
+        let mut deck = Deck::new();
+        deck.remove(&parse_cards("AhKh").unwrap()).unwrap();
+        assert_eq!(deck.len(), 50);
+        assert!(!deck.remaining().contains(&Card::new(Rank::Ace, Suit::Hearts)));
+        assert_eq!(deck.remove(&parse_cards("Ah").unwrap()), Err(String::from("Card 'Ah' is not in the deck.")));
+
+        // A bad card part way through leaves the deck as it was.
+        assert_eq!(deck.remove(&parse_cards("QhJhAh").unwrap()), Err(String::from("Card 'Ah' is not in the deck.")));
+        let queen = Card::new(Rank::Queen, Suit::Hearts);
+        assert_eq!(deck.remove(&[queen, queen]), Err(String::from("Card 'Qh' is not in the deck.")));
+        assert_eq!(deck.len(), 50);
+        assert!(deck.remaining().contains(&queen));
+    }
+
+    #[test]
+    fn deck_with_jokers() {
+        let mut deck = Deck::with_jokers(2);
+        assert_eq!(deck.len(), 54);
+        assert_eq!(deck.deal(), Some(DeckCard::Joker));
+        deck.remove(&[DeckCard::Joker]).unwrap();
+        assert_eq!(deck.remove(&[DeckCard::Joker]), Err(String::from("Card 'Jk' is not in the deck.")));
+    }
+
+    #[test]
+    fn parse_deck_card() {
+        assert_eq!("Jk".parse::<DeckCard>(), Ok(DeckCard::Joker));
+        assert_eq!("Jh".parse::<DeckCard>(), Ok(DeckCard::Card(Card::new(Rank::Jack, Suit::Hearts))));
+        assert_eq!("Jkr".parse::<DeckCard>(), Err(String::from("Card 'Jkr' should be a rank and a suit.")));
+    }
+
+    #[test]
+    fn all_cards_are_distinct() {
+        let mut cards = Card::all();
+        assert_eq!(cards.len(), 52);
+        cards.sort();
+        cards.dedup();
+        assert_eq!(cards.len(), 52);
+    }
+}
@@ -0,0 +1,90 @@
+//! # enumerate
+//!
+//! `enumerate` walks every distinct five card hand, either the 2,598,960 hands of a 52 card deck or the
+//! 6,188 multisets of ranks for suitless hands, and tallies scores by category.
+
+use crate::cards::{Card, Rank};
+use crate::{next_combination, Category, Score};
+
+use std::collections::BTreeMap;
+
+/// Every five card hand that can be dealt from a standard deck.
+///
+/// # Examples
+/// ```
+/// assert_eq!(poker::enumerate::five_card_hands().count(), 2_598_960);
+/// ```
+pub fn five_card_hands() -> impl Iterator<Item = [Card; 5]> {
+    let deck = Card::all();
+    let mut idx = [0, 1, 2, 3, 4];
+    let mut done = false;
+
+    // An array of indexes rather than `for_each_combination` so the hands can be yielded one at a time.
+    std::iter::from_fn(move || {
+        if done {
+            return None;
+        }
+
+        let hand = [deck[idx[0]], deck[idx[1]], deck[idx[2]], deck[idx[3]], deck[idx[4]]];
+        done = !next_combination(&mut idx, deck.len());
+        Some(hand)
+    })
+}
+
+/// Every multiset of five ranks, the distinct hands when suits are ignored.
+///
+/// This includes the 13 five of a kinds, which can be scored as suitless hands.
+pub fn rank_multisets() -> impl Iterator<Item = [Rank; 5]> {
+    let ranks = Rank::ALL;
+    let mut hands: Vec<[Rank; 5]> = Vec::with_capacity(6188);
+    for a in 0..13 {
+        for b in a..13 {
+            for c in b..13 {
+                for d in c..13 {
+                    for e in d..13 {
+                        hands.push([ranks[a], ranks[b], ranks[c], ranks[d], ranks[e]]);
+                    }
+                }
+            }
+        }
+    }
+
+    hands.into_iter()
+}
+
+/// Count scores by their category.
+///
+/// # Examples
+/// ```
+/// use poker::{Category, PokerHand};
+/// use poker::enumerate::{rank_multisets, tally};
+///
+/// let counts = tally(rank_multisets().map(|ranks| PokerHand::from_ranks(ranks).score()));
+/// assert_eq!(counts[&Category::FiveOfAKind], 13);
+/// ```
+pub fn tally<I: IntoIterator<Item = Score>>(scores: I) -> BTreeMap<Category, u64> {
+    let mut counts: BTreeMap<Category, u64> = BTreeMap::new();
+    for score in scores {
+        *counts.entry(score.category()).or_insert(0) += 1;
+    }
+
+    counts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn five_card_hands_start_and_end() {
+        let deck = Card::all();
+        let mut hands = five_card_hands();
+        assert_eq!(hands.next(), Some([deck[0], deck[1], deck[2], deck[3], deck[4]]));
+        assert_eq!(hands.last(), Some([deck[47], deck[48], deck[49], deck[50], deck[51]]));
+    }
+
+    #[test]
+    fn rank_multisets_count() {
+        assert_eq!(rank_multisets().count(), 6188);
+    }
+}
@@ -0,0 +1,569 @@
+//! # equity
+//!
+//! `equity` works out how often each Texas Hold'em hand wins, either by dealing the rest of the board many
+//! times or by trying every possible board.
+
+use crate::cards::{Card, Deck};
+use crate::fast;
+use crate::{for_each_combination, showdown};
+use crate::range::{Combo, Range};
+use crate::rng::Rng;
+use crate::strength::HandRank;
+
+use std::thread;
+
+/// How one player did over all of the runouts.
+#[derive(Debug, PartialEq, Clone)]
+pub struct PlayerEquity {
+    /// Runouts won outright.
+    pub wins: u64,
+    /// Runouts where the pot was split with another player.
+    pub ties: u64,
+    /// The average share of the pot won, from 0 to 1.  A split pot counts as a fraction of a win.
+    pub equity: f64,
+    /// The standard error of `equity`, which is zero for an exact result.
+    pub std_error: f64
+}
+
+/// The result of an equity calculation, one entry for each player in the order they were given.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Equity {
+    pub runouts: u64,
+    pub players: Vec<PlayerEquity>
+}
+
+impl Equity {
+    pub fn win_percent(&self, player: usize) -> f64 {
+        100.0 * self.players[player].wins as f64 / self.runouts as f64
+    }
+
+    pub fn tie_percent(&self, player: usize) -> f64 {
+        100.0 * self.players[player].ties as f64 / self.runouts as f64
+    }
+
+    pub fn equity_percent(&self, player: usize) -> f64 {
+        100.0 * self.players[player].equity
+    }
+}
+
+/// Estimate the equity of two or more hands by dealing `runouts` random completions of the board.
+///
+/// `board` may have 0 to 5 cards and `dead` are cards known to be out of the deck, such as folded hands.
+///
+/// # Examples
+/// ```
+/// use poker::cards::parse_cards;
+/// use poker::equity;
+/// use poker::rng::SplitMix64;
+///
+/// let players = [parse_cards("AhKh").unwrap(), parse_cards("QsQd").unwrap()];
+/// let board = parse_cards("2h7h9c").unwrap();
+/// let result = equity::monte_carlo(&players, &board, &[], 1000, &mut SplitMix64::new(1)).unwrap();
+/// assert_eq!(result.runouts, 1000);
+/// assert!(result.equity_percent(0) > 40.0 && result.equity_percent(0) < 60.0);
+/// ```
+pub fn monte_carlo<R: Rng + ?Sized>(hole_cards: &[Vec<Card>], board: &[Card], dead: &[Card], runouts: u64,
+        rng: &mut R) -> Result<Equity, String> {
+    let deck = remaining_deck(&known_cards(hole_cards, board, dead)?, board)?;
+    if runouts == 0 {
+        return Err(String::from("Required at least 1 runout."));
+    }
+
+    let mut tally = Tally::new(hole_cards.len());
+    let mut full_board = board.to_vec();
+    for _ in 0..runouts {
+        let mut deck = deck.clone();
+        deck.shuffle(rng);
+        full_board.truncate(board.len());
+        full_board.extend(deck.deal_many(5 - board.len())?);
+
+        tally.add(&winners(hole_cards, &full_board));
+    }
+
+    Ok(tally.equity())
+}
+
+/// Work out the exact equity of two or more hands by dealing every possible completion of the board.
+///
+/// The work is split over `threads` threads, `std::thread::available_parallelism` is a good choice.  The
+/// result is the same however many threads are used.
+///
+/// # Examples
+/// ```
+/// use poker::cards::parse_cards;
+/// use poker::equity;
+///
+/// let players = [parse_cards("AhKh").unwrap(), parse_cards("QsQd").unwrap()];
+/// let board = parse_cards("2h7h9cJc").unwrap();
+/// let result = equity::exact(&players, &board, &[], 2).unwrap();
+/// assert_eq!(result.runouts, 44);
+/// assert_eq!(result.players[0].wins, 15);
+/// ```
+pub fn exact(hole_cards: &[Vec<Card>], board: &[Card], dead: &[Card], threads: usize) -> Result<Equity, String> {
+    let deck = remaining_deck(&known_cards(hole_cards, board, dead)?, board)?;
+    if threads == 0 {
+        return Err(String::from("Required at least 1 thread."));
+    }
+
+    let remaining = deck.remaining();
+    let missing = 5 - board.len();
+    if missing == 0 {
+        let mut tally = Tally::new(hole_cards.len());
+        tally.add(&winners(hole_cards, board));
+        return Ok(exact_equity(&tally));
+    }
+
+    let tallies: Vec<Tally> = thread::scope(|scope| {
+        let handles: Vec<_> = (0..threads)
+            .map(|thread| scope.spawn(move || {
+                let mut tally = Tally::new(hole_cards.len());
+                let mut full_board = board.to_vec();
+
+                // Each thread takes every n-th first card and deals the rest of the board from the cards after it.
+                for first in (thread..=remaining.len() - missing).step_by(threads) {
+                    let rest = &remaining[first + 1..];
+                    for_each_combination(rest.len(), missing - 1, |idx| {
+                        full_board.truncate(board.len());
+                        full_board.push(remaining[first]);
+                        full_board.extend(idx.iter().map(|i| rest[*i]));
+                        tally.add(&winners(hole_cards, &full_board));
+                    });
+                }
+
+                tally
+            }))
+            .collect();
+
+        handles.into_iter().map(|handle| handle.join().unwrap()).collect()
+    });
+
+    let mut tally = Tally::new(hole_cards.len());
+    for other in &tallies {
+        tally.merge(other);
+    }
+
+    Ok(exact_equity(&tally))
+}
+
+/// The equity from a tally of every runout, which has no error.
+fn exact_equity(tally: &Tally) -> Equity {
+    let mut equity = tally.equity();
+    for player in equity.players.iter_mut() {
+        player.std_error = 0.0;
+    }
+
+    equity
+}
+
+/// How one combination from a range did against the other ranges.
+#[derive(Debug, PartialEq, Clone)]
+pub struct ComboEquity {
+    pub combo: Combo,
+    /// Runouts where this combination was dealt.
+    pub runouts: u64,
+    /// The average share of the pot won when this combination was dealt, or `None` if it was never dealt.
+    pub equity: Option<f64>
+}
+
+/// The result of a range equity calculation.
+#[derive(Debug, PartialEq, Clone)]
+pub struct RangeEquity {
+    /// The equity of each range as a whole.
+    pub overall: Equity,
+    /// For each range, every combination that could be dealt with the strongest first and those that were never
+    /// dealt last.
+    pub combos: Vec<Vec<ComboEquity>>
+}
+
+/// How many times to try dealing the ranges before giving up because they keep sharing cards.
+const MAX_DEAL_ATTEMPTS: usize = 1000;
+
+/// Estimate the equity of two or more ranges against each other by dealing `runouts` random hands and boards.
+///
+/// Combinations that use a board or dead card are removed and a hand is dealt from each range in proportion to
+/// the weights, dealing again when two ranges share a card.
+///
+/// # Examples
+/// ```
+/// use poker::cards::parse_cards;
+/// use poker::equity;
+/// use poker::range::Range;
+/// use poker::rng::SplitMix64;
+///
+/// let ranges: Vec<Range> = vec!["QQ+, AKs".parse().unwrap(), "22+".parse().unwrap()];
+/// let board = parse_cards("Ah7c2d").unwrap();
+/// let result = equity::range_monte_carlo(&ranges, &board, &[], 500, &mut SplitMix64::new(3)).unwrap();
+/// // The ace on the board leaves 15 combinations of QQ+ and 3 of AKs.
+/// assert_eq!(result.combos[0].len(), 15 + 3);
+/// ```
+pub fn range_monte_carlo<R: Rng + ?Sized>(ranges: &[Range], board: &[Card], dead: &[Card], runouts: u64,
+        rng: &mut R) -> Result<RangeEquity, String> {
+    if ranges.len() < 2 {
+        return Err(format!("Required at least 2 ranges but found {}.", ranges.len()));
+    }
+
+    if runouts == 0 {
+        return Err(String::from("Required at least 1 runout."));
+    }
+
+    let known: Vec<Card> = board.iter().chain(dead.iter()).cloned().collect();
+    let deck = remaining_deck(&known, board)?;
+
+    let mut candidates: Vec<Vec<(Combo, f64)>> = Vec::new();
+    for (idx, range) in ranges.iter().enumerate() {
+        let combos: Vec<(Combo, f64)> = range.combos().filter(|(combo, _)| !combo.conflicts(&known)).collect();
+        if combos.is_empty() {
+            return Err(format!("Range {} has no combinations left after removing the board and dead cards.", idx + 1));
+        }
+        candidates.push(combos);
+    }
+
+    let mut tally = Tally::new(ranges.len());
+    let mut combo_runouts: Vec<Vec<u64>> = candidates.iter().map(|combos| vec![0; combos.len()]).collect();
+    let mut combo_share: Vec<Vec<f64>> = candidates.iter().map(|combos| vec![0.0; combos.len()]).collect();
+    let mut full_board = board.to_vec();
+    for _ in 0..runouts {
+        let dealt = deal_ranges(&candidates, rng)?;
+        let hole_cards: Vec<Vec<Card>> = dealt.iter()
+            .enumerate()
+            .map(|(player, idx)| candidates[player][*idx].0.cards().to_vec())
+            .collect();
+
+        let mut deck = deck.clone();
+        deck.remove(&hole_cards.concat())?;
+        deck.shuffle(rng);
+        full_board.truncate(board.len());
+        full_board.extend(deck.deal_many(5 - board.len())?);
+
+        let winners = winners(&hole_cards, &full_board);
+        tally.add(&winners);
+        for (player, idx) in dealt.iter().enumerate() {
+            combo_runouts[player][*idx] += 1;
+            if winners.contains(&player) {
+                combo_share[player][*idx] += 1.0 / winners.len() as f64;
+            }
+        }
+    }
+
+    let combos = candidates.iter()
+        .enumerate()
+        .map(|(player, combos)| {
+            let mut results: Vec<ComboEquity> = combos.iter()
+                .enumerate()
+                .map(|(idx, (combo, _))| {
+                    let runouts = combo_runouts[player][idx];
+                    let equity = if runouts == 0 { None } else { Some(combo_share[player][idx] / runouts as f64) };
+                    ComboEquity { combo: *combo, runouts, equity }
+                })
+                .collect();
+
+            // `None` sorts below every equity, so combinations that were never dealt go last.
+            results.sort_by(|left, right| right.equity.partial_cmp(&left.equity).unwrap());
+            results
+        })
+        .collect();
+
+    Ok(RangeEquity { overall: tally.equity(), combos })
+}
+
+/// Pick a combination from each range by weight, returning the position of each in its range.
+fn deal_ranges<R: Rng + ?Sized>(candidates: &[Vec<(Combo, f64)>], rng: &mut R) -> Result<Vec<usize>, String> {
+    for _ in 0..MAX_DEAL_ATTEMPTS {
+        let mut dealt: Vec<usize> = Vec::with_capacity(candidates.len());
+        let mut used: Vec<Card> = Vec::with_capacity(candidates.len() * 2);
+        for combos in candidates {
+            let total: f64 = combos.iter().map(|(_, weight)| weight).sum();
+            let mut target = (rng.next_u64() >> 11) as f64 / (1u64 << 53) as f64 * total;
+            let mut pick = combos.len() - 1;
+            for (idx, (_, weight)) in combos.iter().enumerate() {
+                if target < *weight {
+                    pick = idx;
+                    break;
+                }
+                target -= weight;
+            }
+
+            let combo = combos[pick].0;
+            if combo.conflicts(&used) {
+                break;
+            }
+            used.extend(combo.cards().iter());
+            dealt.push(pick);
+        }
+
+        if dealt.len() == candidates.len() {
+            return Ok(dealt);
+        }
+    }
+
+    Err(String::from("The ranges could not be dealt without sharing a card."))
+}
+
+/// Check each player has two hole cards and return every card that is known.
+fn known_cards(hole_cards: &[Vec<Card>], board: &[Card], dead: &[Card]) -> Result<Vec<Card>, String> {
+    if hole_cards.len() < 2 {
+        return Err(format!("Required at least 2 players but found {}.", hole_cards.len()));
+    }
+
+    for hand in hole_cards {
+        if hand.len() != 2 {
+            return Err(format!("Required 2 hole cards but found {}.", hand.len()));
+        }
+    }
+
+    Ok(hole_cards.iter().flatten().chain(board.iter()).chain(dead.iter()).cloned().collect())
+}
+
+/// Check the known cards of an equity calculation and return the deck the rest of the board is dealt from.
+fn remaining_deck(known: &[Card], board: &[Card]) -> Result<Deck, String> {
+    if board.len() > 5 {
+        return Err(format!("Required at most 5 board cards but found {}.", board.len()));
+    }
+
+    for (idx, card) in known.iter().enumerate() {
+        if known[..idx].contains(card) {
+            return Err(format!("Card '{}' appears more than once.", card));
+        }
+    }
+
+    let mut deck = Deck::new();
+    deck.remove(known)?;
+    if deck.len() < 5 - board.len() {
+        return Err(String::from("Not enough cards left to complete the board."));
+    }
+
+    Ok(deck)
+}
+
+/// The players that win with a complete board.
+fn winners(hole_cards: &[Vec<Card>], board: &[Card]) -> Vec<usize> {
+    // The table driven evaluator gives the same answer as `PokerHand::best_of` in a fraction of the time.
+    let scores: Vec<HandRank> = hole_cards.iter()
+        .map(|hand| {
+            let cards: Vec<Card> = hand.iter().chain(board.iter()).cloned().collect();
+            fast::best_of(&cards).unwrap()
+        })
+        .collect();
+
+    showdown(&scores)
+}
+
+/// Running totals for each player.
+///
+/// Only whole numbers are counted so that totals from different threads add up to the same result in any order.
+struct Tally {
+    runouts: u64,
+    // For each player, how many runouts they won with 1, 2, 3... winners.
+    shares: Vec<Vec<u64>>
+}
+
+impl Tally {
+    fn new(players: usize) -> Tally {
+        Tally {
+            runouts: 0,
+            shares: vec![vec![0; players + 1]; players]
+        }
+    }
+
+    fn add(&mut self, winners: &[usize]) {
+        self.runouts += 1;
+        for winner in winners {
+            self.shares[*winner][winners.len()] += 1;
+        }
+    }
+
+    fn merge(&mut self, other: &Tally) {
+        self.runouts += other.runouts;
+        for (shares, other_shares) in self.shares.iter_mut().zip(other.shares.iter()) {
+            for (count, other_count) in shares.iter_mut().zip(other_shares.iter()) {
+                *count += other_count;
+            }
+        }
+    }
+
+    fn equity(&self) -> Equity {
+        let n = self.runouts as f64;
+        let players = self.shares.iter()
+            .map(|shares| {
+                let mut share = 0.0;
+                let mut share_squared = 0.0;
+                for (winners, count) in shares.iter().enumerate().skip(1) {
+                    share += *count as f64 / winners as f64;
+                    share_squared += *count as f64 / (winners * winners) as f64;
+                }
+
+                let equity = share / n;
+                let variance = (share_squared / n - equity * equity).max(0.0);
+                PlayerEquity {
+                    wins: shares[1],
+                    ties: shares[2..].iter().sum(),
+                    equity,
+                    std_error: (variance / n).sqrt()
+                }
+            })
+            .collect();
+
+        Equity { runouts: self.runouts, players }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cards::parse_cards;
+    use crate::rng::SplitMix64;
+
+    fn players(hands: &[&str]) -> Vec<Vec<Card>> {
+        hands.iter().map(|hand| parse_cards(hand).unwrap()).collect()
+    }
+
+    #[test]
+    fn aces_against_kings() {
+        let result = monte_carlo(&players(&["AsAh", "KsKh"]), &[], &[], 5000, &mut SplitMix64::new(11)).unwrap();
+
+        // The exact answer is about 82.6% for the aces.
+        let aces = &result.players[0];
+        assert!((aces.equity - 0.826).abs() < 4.0 * aces.std_error, "{:?}", result);
+        assert!((result.equity_percent(0) + result.equity_percent(1) - 100.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn same_seed_same_result() {
+        let hands = players(&["AhKh", "QsQd"]);
+        let board = parse_cards("2h7h9c").unwrap();
+        let first = monte_carlo(&hands, &board, &[], 500, &mut SplitMix64::new(5)).unwrap();
+        let second = monte_carlo(&hands, &board, &[], 500, &mut SplitMix64::new(5)).unwrap();
+        assert_eq!(first, second);
+    }
+
+    #[test]
+    fn complete_board_is_exact() {
+        let board = parse_cards("2h7h9cTdJs").unwrap();
+        let result = monte_carlo(&players(&["AhKh", "QsQd", "8c8d"]), &board, &[], 10, &mut SplitMix64::new(1)).unwrap();
+
+        assert_eq!(result.players[2], PlayerEquity{ wins: 10, ties: 0, equity: 1.0, std_error: 0.0 });
+        assert_eq!(result.win_percent(0), 0.0);
+    }
+
+    #[test]
+    fn split_pot() {
+        let board = parse_cards("AsKsQsJsTs").unwrap();
+        let result = monte_carlo(&players(&["2c3c", "4d5d"]), &board, &[], 3, &mut SplitMix64::new(1)).unwrap();
+
+        assert_eq!(result.players[0], PlayerEquity{ wins: 0, ties: 3, equity: 0.5, std_error: 0.0 });
+        assert_eq!(result.tie_percent(1), 100.0);
+    }
+
+    #[test]
+    fn dead_cards_are_not_dealt() {
+        // With every other heart dead the flush can't come in.
+        let dead = parse_cards("2h3h4h5h6h8hThJhQh").unwrap();
+        let board = parse_cards("7h9hJc2c").unwrap();
+        let result = monte_carlo(&players(&["AhKh", "JsJd"]), &board, &dead, 200, &mut SplitMix64::new(3)).unwrap();
+
+        assert_eq!(result.players[0].wins, 0);
+    }
+
+    #[test]
+    fn exact_on_the_flop() {
+        let hands = players(&["AhKh", "QsQd"]);
+        let board = parse_cards("2h7h9c").unwrap();
+        let result = exact(&hands, &board, &[], 3).unwrap();
+
+        assert_eq!(result.runouts, 990);
+        assert_eq!(result.players[0].wins + result.players[1].wins + result.players[0].ties, 990);
+        assert_eq!(result.players[0].ties, result.players[1].ties);
+        assert_eq!(result.players[0].std_error, 0.0);
+    }
+
+    #[test]
+    fn exact_agrees_with_monte_carlo() {
+        let hands = players(&["AhKh", "QsQd"]);
+        let board = parse_cards("2h7h9c").unwrap();
+        let exact = exact(&hands, &board, &[], 2).unwrap();
+        let sampled = monte_carlo(&hands, &board, &[], 2000, &mut SplitMix64::new(8)).unwrap();
+
+        let error = sampled.players[0].std_error;
+        assert!((exact.players[0].equity - sampled.players[0].equity).abs() < 4.0 * error);
+    }
+
+    #[test]
+    fn exact_is_the_same_for_any_number_of_threads() {
+        let hands = players(&["AhKh", "QsQd", "7c7d"]);
+        let board = parse_cards("2h7h9c").unwrap();
+        let one = exact(&hands, &board, &[], 1).unwrap();
+        for threads in 2..6 {
+            assert_eq!(exact(&hands, &board, &[], threads).unwrap(), one);
+        }
+    }
+
+    #[test]
+    fn exact_split_pot() {
+        let board = parse_cards("AsKsQsJsTs").unwrap();
+        let result = exact(&players(&["2c3c", "4d5d"]), &board, &[], 4).unwrap();
+
+        assert_eq!(result.runouts, 1);
+        assert_eq!(result.players[0], PlayerEquity{ wins: 0, ties: 1, equity: 0.5, std_error: 0.0 });
+    }
+
+    #[test]
+    fn range_against_range() {
+        let ranges: Vec<Range> = vec!["AA".parse().unwrap(), "KK".parse().unwrap()];
+        let result = range_monte_carlo(&ranges, &[], &[], 3000, &mut SplitMix64::new(21)).unwrap();
+
+        let aces = &result.overall.players[0];
+        assert!((aces.equity - 0.82).abs() < 4.0 * aces.std_error, "{:?}", aces);
+        assert_eq!(result.combos[0].len(), 6);
+        assert_eq!(result.combos[0].iter().map(|combo| combo.runouts).sum::<u64>(), 3000);
+    }
+
+    #[test]
+    fn range_conflicts_are_removed() {
+        let ranges: Vec<Range> = vec!["AsKs".parse().unwrap(), "AsAh, KdKc, QhQd".parse().unwrap()];
+        let board = parse_cards("Qc7d2s").unwrap();
+        let result = range_monte_carlo(&ranges, &board, &[], 200, &mut SplitMix64::new(4)).unwrap();
+
+        // AsAh shares a card with the only hand in the first range and QhQd shares nothing but is still dealt.
+        let second = &result.combos[1];
+        assert_eq!(second.len(), 3);
+        let dealt: u64 = second.iter().filter(|combo| combo.combo.to_string() == "AsAh").map(|combo| combo.runouts).sum();
+        assert_eq!(dealt, 0);
+
+        // Sets of queens are the best hand in the second range, and AsAh has no equity because it was never dealt.
+        assert_eq!(second[0].combo.to_string(), "QhQd");
+        assert_eq!(second[2].combo.to_string(), "AsAh");
+        assert_eq!(second[2].equity, None);
+    }
+
+    #[test]
+    fn range_weights() {
+        let ranges: Vec<Range> = vec!["AsAh, KsKh:0.25".parse().unwrap(), "QdQc".parse().unwrap()];
+        let result = range_monte_carlo(&ranges, &[], &[], 2000, &mut SplitMix64::new(9)).unwrap();
+
+        let aces = result.combos[0].iter().find(|combo| combo.combo.to_string() == "AsAh").unwrap().runouts;
+        let kings = result.combos[0].iter().find(|combo| combo.combo.to_string() == "KsKh").unwrap().runouts;
+        assert!(aces > 3 * kings && aces < 5 * kings, "{} {}", aces, kings);
+    }
+
+    #[test]
+    fn range_bad_input() {
+        let mut rng = SplitMix64::new(1);
+        let aces: Range = "AA".parse().unwrap();
+        let board = parse_cards("AsAhAd").unwrap();
+        assert_eq!(range_monte_carlo(std::slice::from_ref(&aces), &[], &[], 10, &mut rng), Err(String::from("Required at least 2 ranges but found 1.")));
+        assert_eq!(range_monte_carlo(&[aces.clone(), "KK".parse().unwrap()], &board, &[], 10, &mut rng),
+            Err(String::from("Range 1 has no combinations left after removing the board and dead cards.")));
+        assert_eq!(range_monte_carlo(&["AsAh".parse().unwrap(), "AsAh".parse().unwrap()], &[], &[], 10, &mut rng),
+            Err(String::from("The ranges could not be dealt without sharing a card.")));
+    }
+
+    #[test]
+    fn bad_input() {
+        let mut rng = SplitMix64::new(1);
+        assert_eq!(monte_carlo(&players(&["AhKh"]), &[], &[], 10, &mut rng), Err(String::from("Required at least 2 players but found 1.")));
+        assert_eq!(monte_carlo(&players(&["AhKh", "Qs"]), &[], &[], 10, &mut rng), Err(String::from("Required 2 hole cards but found 1.")));
+        assert_eq!(monte_carlo(&players(&["AhKh", "AhQs"]), &[], &[], 10, &mut rng), Err(String::from("Card 'Ah' appears more than once.")));
+        assert_eq!(monte_carlo(&players(&["AhKh", "QsQd"]), &[], &[], 0, &mut rng), Err(String::from("Required at least 1 runout.")));
+        assert_eq!(exact(&players(&["AhKh", "QsQd"]), &[], &[], 0), Err(String::from("Required at least 1 thread.")));
+    }
+}
@@ -0,0 +1,213 @@
+//! # fast
+//!
+//! `fast` is a table driven evaluator in the style of Cactus Kev's.  It gives the same answers as
+//! `PokerHand::score` and `PokerHand::best_of` as a `HandRank`, without allocating for each hand, which
+//! matters when simulating hundreds of millions of hands.
+//!
+//! Each card is packed in to a `u32`:
+//!
+//! ```text
+//! +--------+--------+--------+--------+
+//! |xxxbbbbb|bbbbbbbb|cdhsrrrr|xxpppppp|
+//! +--------+--------+--------+--------+
+//! ```
+//!
+//! where `b` is a bit for the rank, `cdhs` is a bit for the suit, `r` is the rank and `p` is a prime for
+//! the rank.  A flush is found by and-ing the suit bits, five different ranks by or-ing the rank bits and
+//! everything else by the product of the primes.
+
+use crate::cards::{Card, Suit};
+use crate::enumerate::rank_multisets;
+use crate::strength::HandRank;
+use crate::{card_seq, next_combination, PokerHand};
+
+use std::sync::OnceLock;
+
+/// One prime for each rank, from two to ace.
+const PRIMES: [u32; 13] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41];
+
+struct Tables {
+    // Indexed by the rank bits of a flush.
+    flushes: Vec<u16>,
+    // Indexed by the rank bits of five different ranks that are not a flush.
+    unique5: Vec<u16>,
+    // The product of the primes for every other hand, sorted for a binary search.
+    products: Vec<(u32, u16)>
+}
+
+/// Pack a card in to the evaluator's representation.
+pub fn encode(card: Card) -> u32 {
+    let rank = card_seq(&card.rank) as u32 - 2;
+    let suit = match card.suit {
+        Suit::Clubs => 0x8000,
+        Suit::Diamonds => 0x4000,
+        Suit::Hearts => 0x2000,
+        Suit::Spades => 0x1000
+    };
+
+    (1 << (16 + rank)) | suit | (rank << 8) | PRIMES[rank as usize]
+}
+
+/// Score five cards, the same as `PokerHand::new(cards).score().strength()`.
+///
+/// # Examples
+/// ```
+/// use poker::{fast, PokerHand};
+///
+/// let cards = PokerHand::from_str("AhKhQhJhTh").unwrap().cards().unwrap();
+/// assert_eq!(fast::score(&cards), Ok(poker::strength::HandRank::BEST));
+/// ```
+pub fn score(cards: &[Card; 5]) -> Result<HandRank, String> {
+    check_distinct(cards)?;
+    score_encoded([encode(cards[0]), encode(cards[1]), encode(cards[2]), encode(cards[3]), encode(cards[4])])
+}
+
+/// Score five cards that have already been packed with `encode`.  Five cards of one rank can only come from a
+/// repeated card and are an error.
+pub fn score_encoded(cards: [u32; 5]) -> Result<HandRank, String> {
+    let tables = tables();
+    let bits = ((cards[0] | cards[1] | cards[2] | cards[3] | cards[4]) >> 16) as usize;
+
+    let value = if cards[0] & cards[1] & cards[2] & cards[3] & cards[4] & 0xF000 != 0 {
+        tables.flushes[bits]
+    } else if tables.unique5[bits] != 0 {
+        tables.unique5[bits]
+    } else {
+        let product = cards.iter().map(|card| card & 0xFF).product::<u32>();
+        match tables.products.binary_search_by_key(&product, |(product, _)| *product) {
+            Ok(idx) => tables.products[idx].1,
+            Err(_) => 0
+        }
+    };
+
+    // Only five cards of one rank are missing from the tables.
+    HandRank::new(value).ok_or_else(|| String::from("Required at most 4 cards of a rank but found 5."))
+}
+
+/// Score the best five of 5, 6 or 7 cards, the same as `PokerHand::best_of(cards)`.
+pub fn best_of(cards: &[Card]) -> Result<HandRank, String> {
+    if cards.len() < 5 || cards.len() > 7 {
+        return Err(format!("Required 5 to 7 cards but found {}.", cards.len()));
+    }
+
+    check_distinct(cards)?;
+
+    let mut encoded = [0u32; 7];
+    for (packed, card) in encoded.iter_mut().zip(cards) {
+        *packed = encode(*card);
+    }
+
+    // Step through the combinations in a fixed array rather than `for_each_combination`, which allocates.
+    let mut best = HandRank::WORST;
+    let mut idx = [0, 1, 2, 3, 4];
+    loop {
+        let rank = score_encoded([encoded[idx[0]], encoded[idx[1]], encoded[idx[2]], encoded[idx[3]], encoded[idx[4]]])?;
+        if rank > best {
+            best = rank;
+        }
+
+        if !next_combination(&mut idx, cards.len()) {
+            return Ok(best);
+        }
+    }
+}
+
+/// Report the first card that appears more than once.
+fn check_distinct(cards: &[Card]) -> Result<(), String> {
+    for (idx, card) in cards.iter().enumerate() {
+        if cards[..idx].contains(card) {
+            return Err(format!("Card '{}' appears more than once.", card));
+        }
+    }
+
+    Ok(())
+}
+
+fn tables() -> &'static Tables {
+    static TABLES: OnceLock<Tables> = OnceLock::new();
+    TABLES.get_or_init(|| {
+        let mut tables = Tables {
+            flushes: vec![0; 1 << 13],
+            unique5: vec![0; 1 << 13],
+            products: Vec::new()
+        };
+
+        // The tables are filled from the slower evaluator, one hand for every multiset of ranks.
+        for hand in rank_multisets() {
+            if hand[0] == hand[4] {
+                continue;
+            }
+
+            let flush = hand.map(|rank| Card::new(rank, Suit::Spades));
+            let encoded = flush.map(encode);
+            let bits = (encoded.iter().fold(0, |bits, card| bits | card) >> 16) as usize;
+            let strength = PokerHand::from_ranks(hand).score().strength().unwrap().value();
+
+            if hand.windows(2).all(|pair| pair[0] != pair[1]) {
+                tables.flushes[bits] = PokerHand::new(flush).score().strength().unwrap().value();
+                tables.unique5[bits] = strength;
+            } else {
+                let product = encoded.iter().map(|card| card & 0xFF).product::<u32>();
+                tables.products.push((product, strength));
+            }
+        }
+
+        tables.products.sort();
+        tables
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cards::parse_cards;
+
+    fn five(cards: &str) -> [Card; 5] {
+        let cards = parse_cards(cards).unwrap();
+        [cards[0], cards[1], cards[2], cards[3], cards[4]]
+    }
+
+    #[test]
+    fn encode_king_of_diamonds() {
+        // The example from Cactus Kev's write up.
+        assert_eq!(encode("Kd".parse().unwrap()), 0x0800_4B25);
+    }
+
+    #[test]
+    fn table_sizes() {
+        assert_eq!(tables().flushes.iter().filter(|value| **value != 0).count(), 1287);
+        assert_eq!(tables().unique5.iter().filter(|value| **value != 0).count(), 1287);
+        assert_eq!(tables().products.len(), 4888);
+    }
+
+    #[test]
+    fn score_matches_slow_score() {
+        for hand in ["AhKhQhJhTh", "2c2d2h2s3c", "AsAhKsKdKc", "2h9hKh4h7h", "Ad2c3h4s5d", "3c3dAhKhQs", "7c5d4h3s2c"].iter() {
+            let cards = five(hand);
+            assert_eq!(score(&cards).ok(), PokerHand::new(cards).score().strength(), "{}", hand);
+        }
+    }
+
+    #[test]
+    fn best_of_seven() {
+        let cards = parse_cards("AhKh QhJhTh2c3d").unwrap();
+        assert_eq!(best_of(&cards), Ok(HandRank::BEST));
+        assert_eq!(best_of(&cards).unwrap().score(), PokerHand::best_of(&cards).unwrap().score);
+    }
+
+    #[test]
+    fn bad_input() {
+        let ace = Card::new(crate::cards::Rank::Ace, Suit::Spades);
+        let error = Err(String::from("Card 'As' appears more than once."));
+        assert_eq!(score(&[ace; 5]), error);
+        assert_eq!(best_of(&[ace; 6]), error);
+        let mut cards = parse_cards("AhKhQhJhTh").unwrap();
+        cards.push(cards[2]);
+        assert_eq!(best_of(&cards), Err(String::from("Card 'Qh' appears more than once.")));
+
+        // Packed cards aren't checked for repeats, so five of one rank is the only sign of one.
+        assert_eq!(score_encoded([encode(ace); 5]), Err(String::from("Required at most 4 cards of a rank but found 5.")));
+        assert_eq!(best_of(&parse_cards("AhKhQhJh").unwrap()), Err(String::from("Required 5 to 7 cards but found 4.")));
+        assert_eq!(best_of(&parse_cards("AhKhQhJhTh9h8h7h").unwrap()), Err(String::from("Required 5 to 7 cards but found 8.")));
+    }
+}
@@ -0,0 +1,243 @@
+//! # hilo
+//!
+//! `hilo` scores split pot games, such as Omaha Hi/Lo and Stud Hi/Lo, where the best high hand wins half of
+//! the pot and the best ace-to-five low wins the other half.  A low only counts when it is eight or better,
+//! five different ranks no higher than an eight, and when nobody has one the high hand wins the whole pot.
+
+use crate::cards::{Card, Rank};
+use crate::lowball::{ace_to_five, best_ace_to_five, BestLow, LowScore};
+use crate::{card_seq, for_each_combination, omaha, showdown, BestHand, Category, PokerHand, Score};
+
+/// The best high hand and the best qualifying low hand a player can make.
+#[derive(Debug, PartialEq, Clone)]
+pub struct HiLo {
+    pub high: BestHand,
+    pub low: Option<BestLow>
+}
+
+/// True if a low is eight or better.
+///
+/// # Examples
+/// ```
+/// use poker::PokerHand;
+/// use poker::hilo::qualifies;
+/// use poker::lowball::ace_to_five;
+///
+/// assert!(qualifies(&ace_to_five(PokerHand::from_str("8765A").unwrap().ranks())));
+/// assert!(!qualifies(&ace_to_five(PokerHand::from_str("9432A").unwrap().ranks())));
+/// ```
+pub fn qualifies(low: &LowScore) -> bool {
+    low.category() == Category::HighCard && (low.high_card() == Rank::Ace || card_seq(&low.high_card()) <= 8)
+}
+
+/// Score a Stud Hi/Lo hand, the best high and the best qualifying low from 5 to 7 cards.
+///
+/// # Examples
+/// ```
+/// use poker::cards::parse_cards;
+/// use poker::hilo;
+///
+/// let hand = hilo::stud(&parse_cards("Ah2d 3c4s5h Kd Kc").unwrap()).unwrap();
+/// assert_eq!(hand.high.score, poker::Score::Straight(poker::cards::Rank::Five));
+/// assert_eq!(hand.low.unwrap().score.to_string(), "5-4-3-2-A");
+/// ```
+pub fn stud(cards: &[Card]) -> Result<HiLo, String> {
+    let high = PokerHand::best_of(cards)?;
+    let low = best_ace_to_five(cards)?;
+    Ok(HiLo { high, low: Some(low).filter(|low| qualifies(&low.score)) })
+}
+
+/// Score an Omaha Hi/Lo hand, using exactly two of the hole cards and three of the board for each half.  The
+/// high and the low may use different hole cards.
+///
+/// # Examples
+/// ```
+/// use poker::cards::parse_cards;
+/// use poker::hilo;
+///
+/// let hand = hilo::omaha(&parse_cards("Ah2dKsKd").unwrap(), &parse_cards("3c7s8hKc9d").unwrap()).unwrap();
+/// assert_eq!(hand.low.unwrap().score.to_string(), "8-7-3-2-A");
+/// ```
+pub fn omaha(hole_cards: &[Card], board: &[Card]) -> Result<HiLo, String> {
+    let best = omaha::best_hand(hole_cards, board)?;
+    let high = BestHand {
+        score: best.score,
+        cards: [best.hole_cards[0], best.hole_cards[1], best.board_cards[0], best.board_cards[1], best.board_cards[2]]
+    };
+
+    let mut low: Option<BestLow> = None;
+    for_each_combination(hole_cards.len(), 2, |hole_idx| {
+        for_each_combination(board.len(), 3, |board_idx| {
+            let hand = [hole_cards[hole_idx[0]], hole_cards[hole_idx[1]], board[board_idx[0]], board[board_idx[1]], board[board_idx[2]]];
+            let score = ace_to_five(&hand.map(|card| card.rank));
+            let better = match &low {
+                Some(current) => score > current.score,
+                None => qualifies(&score)
+            };
+
+            if better {
+                low = Some(BestLow { score, cards: hand });
+            }
+        });
+    });
+
+    Ok(HiLo { high, low })
+}
+
+/// Split a pot between the hands at a showdown, returning the chips won by each hand.
+///
+/// The hands should be in seat order starting from the left of the button.  The high hand wins half of the
+/// pot and the best qualifying low the other half, or the high hand scoops the pot if there is no low.  Tied
+/// hands share their half, so a player may win a quarter.  An odd chip between the halves goes to the high
+/// hand, and odd chips within a half go one each to the winners closest to the left of the button.
+///
+/// # Examples
+/// ```
+/// use poker::cards::parse_cards;
+/// use poker::hilo;
+///
+/// let board = parse_cards("2c3d8hKsKc").unwrap();
+/// let hands = [
+///     hilo::omaha(&parse_cards("AhKdQcJs").unwrap(), &board).unwrap(),
+///     hilo::omaha(&parse_cards("As4h9c9d").unwrap(), &board).unwrap(),
+///     hilo::omaha(&parse_cards("Ad4cTdTh").unwrap(), &board).unwrap(),
+/// ];
+/// // The first hand wins the high half, the other two split the low half.
+/// assert_eq!(hilo::split_pot(101, &hands), vec![51, 25, 25]);
+/// ```
+pub fn split_pot(pot: u64, hands: &[HiLo]) -> Vec<u64> {
+    let mut won = vec![0; hands.len()];
+    if hands.is_empty() {
+        return won;
+    }
+
+    let highs: Vec<&Score> = hands.iter().map(|hand| &hand.high.score).collect();
+    let lows: Vec<(usize, &LowScore)> = hands.iter().enumerate()
+        .filter_map(|(idx, hand)| hand.low.as_ref().map(|low| (idx, &low.score)))
+        .collect();
+
+    if lows.is_empty() {
+        award(pot, &showdown(&highs), &mut won);
+    } else {
+        let low_scores: Vec<&LowScore> = lows.iter().map(|(_, low)| *low).collect();
+        let low_winners: Vec<usize> = showdown(&low_scores).iter().map(|idx| lows[*idx].0).collect();
+        award(pot - pot / 2, &showdown(&highs), &mut won);
+        award(pot / 2, &low_winners, &mut won);
+    }
+
+    won
+}
+
+/// Share some chips between the winners, who are in seat order, giving odd chips to the first winners.
+fn award(chips: u64, winners: &[usize], won: &mut [u64]) {
+    let count = winners.len() as u64;
+    for (place, idx) in winners.iter().enumerate() {
+        won[*idx] += chips / count + if (place as u64) < chips % count { 1 } else { 0 };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cards::parse_cards;
+
+    fn omaha_hand(hole: &str, board: &str) -> HiLo {
+        omaha(&parse_cards(hole).unwrap(), &parse_cards(board).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn eight_or_better() {
+        let low = |hand: &str| ace_to_five(PokerHand::from_str(hand).unwrap().ranks());
+        assert!(qualifies(&low("5432A")));
+        assert!(qualifies(&low("87654")));
+        assert!(!qualifies(&low("97654")));
+        assert!(!qualifies(&low("AA234")));
+    }
+
+    #[test]
+    fn omaha_low_needs_two_hole_cards() {
+        // Three low cards on the board and only one low card in hand.
+        let hand = omaha_hand("AhKdKsQc", "2c3d4hJsTc");
+        assert_eq!(hand.low, None);
+
+        let hand = omaha_hand("Ah2dKsQc", "3c4d8hJsTc");
+        let low = hand.low.unwrap();
+        assert_eq!(low.score.to_string(), "8-4-3-2-A");
+        assert_eq!(hand.high.score.category(), Category::HighCard);
+    }
+
+    #[test]
+    fn omaha_no_low_on_the_board() {
+        // Only two low cards on the board means nobody can have a low.
+        let hand = omaha_hand("Ah2d3s4c", "9c8dKhJsTc");
+        assert_eq!(hand.low, None);
+    }
+
+    #[test]
+    fn stud_high_and_low() {
+        let hand = stud(&parse_cards("AhAd 2c3c8s4h Kd").unwrap()).unwrap();
+        assert_eq!(hand.high.score.category(), Category::Pair);
+        assert_eq!(hand.low.unwrap().score.to_string(), "8-4-3-2-A");
+
+        let hand = stud(&parse_cards("AhAd 2c9c9s4h Kd").unwrap()).unwrap();
+        assert_eq!(hand.low, None);
+        assert_eq!(stud(&parse_cards("AhAd2c9c").unwrap()), Err(String::from("Required 5 to 7 cards but found 4.")));
+    }
+
+    #[test]
+    fn high_scoops_without_a_low() {
+        let board = "9c8dKhJsTc";
+        let hands = [omaha_hand("AhAd2s3c", board), omaha_hand("Qh9h2c3s", board)];
+        assert_eq!(split_pot(100, &hands), vec![0, 100]);
+    }
+
+    #[test]
+    fn one_hand_scoops_both_halves() {
+        let board = "2c3d4hKsQc";
+        let hands = [omaha_hand("Ah5d9s9c", board), omaha_hand("KhKdTc8s", board)];
+        assert_eq!(hands[0].high.score, Score::Straight(Rank::Five));
+        assert_eq!(split_pot(100, &hands), vec![100, 0]);
+    }
+
+    #[test]
+    fn low_half_is_split() {
+        // Both low hands are A-2-3-4-8 and the third player has the high.
+        let board = "3c4d8hKsKc";
+        let hands = [
+            omaha_hand("Ah2dJcJs", board),
+            omaha_hand("As2cQhQs", board),
+            omaha_hand("KdKhTc9s", board),
+        ];
+        assert_eq!(split_pot(100, &hands), vec![25, 25, 50]);
+    }
+
+    #[test]
+    fn quartered() {
+        // The first player wins the high with kings full and ties the low with the second player.
+        let board = "3c4d8hKsKc";
+        let hands = [
+            omaha_hand("Ah2dKd8s", board),
+            omaha_hand("As2cQhQs", board),
+            omaha_hand("9s9dTcTs", board),
+        ];
+        assert_eq!(hands[0].high.score.category(), Category::FullHouse);
+        assert_eq!(split_pot(100, &hands), vec![75, 25, 0]);
+        // 51 for the high, then the low half of 51 is 26 and 25 with the odd chip to the first seat.
+        assert_eq!(split_pot(102, &hands), vec![77, 25, 0]);
+        assert_eq!(split_pot(101, &hands), vec![76, 25, 0]);
+    }
+
+    #[test]
+    fn odd_chips() {
+        let board = "3c4d8hKsKc";
+        let hands = [
+            omaha_hand("Ah2dJcJs", board),
+            omaha_hand("As2cQhQs", board),
+            omaha_hand("KdKhTc9s", board),
+        ];
+        // The odd chip between halves goes high, then the low half's odd chip goes to the first seat.
+        assert_eq!(split_pot(103, &hands), vec![26, 25, 52]);
+        assert_eq!(split_pot(1, &hands), vec![0, 0, 1]);
+        assert_eq!(split_pot(0, &[]), Vec::<u64>::new());
+    }
+}
@@ -0,0 +1,444 @@
+//! # holdem
+//!
+//! `holdem` plays a single hand of Texas Hold'em one action at a time, from posting the blinds to the
+//! showdown.  There is no I/O and no randomness, the deck is shuffled by the caller, so the same actions on the
+//! same deck always play out the same way.  The betting on each street is run by `betting::Round`.
+//!
+//! ```
+//! use poker::cards::Deck;
+//! use poker::holdem::{Action, Hand, Stakes};
+//! use poker::rng::SplitMix64;
+//!
+//! let mut deck = Deck::new();
+//! deck.shuffle(&mut SplitMix64::new(7));
+//!
+//! let stakes = Stakes { small_blind: 1, big_blind: 2, ante: 0 };
+//! let mut hand = Hand::new(&[100, 100, 100], 0, stakes, deck).unwrap();
+//! while !hand.is_over() {
+//!     let action = if hand.legal_actions().contains(&poker::holdem::Legal::Check) { Action::Check } else { Action::Call };
+//!     hand.apply(action).unwrap();
+//! }
+//! assert_eq!(hand.board().len(), 5);
+//! assert_eq!(hand.winnings().unwrap().iter().sum::<u64>(), 6);
+//! ```
+//!
+//! At the end of the hand any uncalled bet is returned and the pot is split in to side pots by `pot::side_pots`,
+//! each won by the best eligible hand.
+
+pub use crate::betting::{Action, Legal, Limit};
+
+use crate::cards::{Card, Deck};
+use crate::table::{self, Next, Table};
+use crate::{PokerHand, Score};
+
+/// The most players a 52 card deck can deal to, with a card burned before each street.
+pub const MAX_PLAYERS: usize = 22;
+
+/// The forced bets.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub struct Stakes {
+    pub small_blind: u64,
+    pub big_blind: u64,
+    /// Paid by every player before the blinds, or zero for no ante.
+    pub ante: u64
+}
+
+/// The betting rounds of a hand.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash, PartialOrd, Ord)]
+pub enum Street {
+    Preflop,
+    Flop,
+    Turn,
+    River
+}
+
+/// A player in the hand, with two hole cards.
+pub type Player = table::Player<[Card; 2]>;
+
+/// A hand of Texas Hold'em in progress.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Hand {
+    stakes: Stakes,
+    limit: Limit,
+    table: Table<[Card; 2]>,
+    button: usize,
+    deck: Deck,
+    board: Vec<Card>,
+    street: Street
+}
+
+impl Hand {
+    /// Seat the players for a no limit hand, post the antes and blinds and deal the hole cards, one at a time
+    /// starting to the left of the button.  Heads up the button posts the small blind.
+    pub fn new(stacks: &[u64], button: usize, stakes: Stakes, deck: Deck) -> Result<Hand, String> {
+        Hand::with_limit(stacks, button, stakes, Limit::NoLimit, deck)
+    }
+
+    /// Seat the players for a hand with any betting structure.  In fixed limit a bet is the big blind before
+    /// the turn and twice the big blind after.
+    pub fn with_limit(stacks: &[u64], button: usize, stakes: Stakes, limit: Limit, mut deck: Deck) -> Result<Hand, String> {
+        let count = stacks.len();
+        if !(2..=MAX_PLAYERS).contains(&count) {
+            return Err(format!("Required 2 to {} players but found {}.", MAX_PLAYERS, count));
+        }
+
+        if button >= count {
+            return Err(format!("Seat {} is not at the table.", button));
+        }
+
+        if let Some(seat) = stacks.iter().position(|stack| *stack == 0) {
+            return Err(format!("Seat {} has no chips.", seat));
+        }
+
+        if stakes.big_blind == 0 || stakes.small_blind > stakes.big_blind {
+            return Err(String::from("The big blind must be at least one chip and no smaller than the small blind."));
+        }
+
+        let needed = 2 * count + 8;
+        if deck.len() < needed {
+            return Err(format!("Required {} cards but only {} remain.", needed, deck.len()));
+        }
+
+        let mut hole_cards = vec![Vec::new(); count];
+        for _ in 0..2 {
+            for offset in 1..=count {
+                hole_cards[(button + offset) % count].push(deck.deal().unwrap());
+            }
+        }
+
+        let players: Vec<Player> = stacks.iter().zip(hole_cards).map(|(stack, cards)| {
+            Player::new(*stack, [cards[0], cards[1]])
+        }).collect();
+
+        let mut hand = Hand {
+            stakes,
+            limit,
+            table: Table::new(players, stakes.ante, limit, stakes.big_blind),
+            button,
+            deck,
+            board: Vec::new(),
+            street: Street::Preflop
+        };
+
+        let (small_blind, big_blind) = hand.blinds();
+        hand.table.round.post(small_blind, stakes.small_blind);
+        hand.table.round.post(big_blind, stakes.big_blind);
+        hand.table.round.start(big_blind);
+        hand.table.sync();
+        hand.advance();
+        Ok(hand)
+    }
+
+    /// The seats of the small blind and the big blind.
+    pub fn blinds(&self) -> (usize, usize) {
+        let count = self.table.players().len();
+        match count {
+            2 => (self.button, (self.button + 1) % count),
+            _ => ((self.button + 1) % count, (self.button + 2) % count)
+        }
+    }
+
+    pub fn limit(&self) -> Limit {
+        self.limit
+    }
+
+    pub fn players(&self) -> &[Player] {
+        self.table.players()
+    }
+
+    pub fn button(&self) -> usize {
+        self.button
+    }
+
+    pub fn board(&self) -> &[Card] {
+        &self.board
+    }
+
+    pub fn street(&self) -> Street {
+        self.street
+    }
+
+    /// The seat of the player to act, or `None` once the hand is over.
+    pub fn to_act(&self) -> Option<usize> {
+        self.table.round.to_act()
+    }
+
+    /// The total bet on this street that a player must match to stay in the hand.
+    pub fn current_bet(&self) -> u64 {
+        self.table.round.current_bet()
+    }
+
+    /// All the chips in the middle, including bets on this street.
+    pub fn pot(&self) -> u64 {
+        self.table.pot()
+    }
+
+    pub fn is_over(&self) -> bool {
+        self.table.is_over()
+    }
+
+    /// The chips each seat won from the pot, once the hand is over.
+    pub fn winnings(&self) -> Option<&[u64]> {
+        self.table.winnings()
+    }
+
+    /// The actions the player to act may take, empty once the hand is over.
+    pub fn legal_actions(&self) -> Vec<Legal> {
+        self.table.legal_actions()
+    }
+
+    /// Take an action for the player to act and move the hand on, dealing the next street or going to the
+    /// showdown when the betting is done.
+    pub fn apply(&mut self, action: Action) -> Result<(), String> {
+        self.table.apply(action)?;
+        self.advance();
+        Ok(())
+    }
+
+    /// The size of a bet on the current street.
+    fn bet_size(&self) -> u64 {
+        match (self.limit, self.street) {
+            (Limit::FixedLimit{ .. }, Street::Turn) | (Limit::FixedLimit{ .. }, Street::River) => 2 * self.stakes.big_blind,
+            _ => self.stakes.big_blind
+        }
+    }
+
+    /// Deal the next street, or finish the hand, once nobody has to act.
+    fn advance(&mut self) {
+        loop {
+            match self.table.next(self.street == Street::River) {
+                Next::Act => return,
+                Next::Deal => {
+                    self.deal_street();
+                    self.table.new_round(self.bet_size());
+                    self.table.round.start(self.button);
+                },
+                Next::Showdown => {
+                    while self.board.len() < 5 {
+                        self.deal_street();
+                    }
+                    self.finish();
+                    return;
+                },
+                Next::Uncontested => {
+                    self.finish();
+                    return;
+                }
+            }
+        }
+    }
+
+    /// Burn a card and deal the flop, turn or river.
+    fn deal_street(&mut self) {
+        self.deck.deal();
+        let (street, cards) = match self.street {
+            Street::Preflop => (Street::Flop, 3),
+            Street::Flop => (Street::Turn, 1),
+            _ => (Street::River, 1)
+        };
+
+        self.street = street;
+        self.board.extend(self.deck.deal_many(cards).unwrap());
+    }
+
+    /// Split the pot between the best hands, with odd chips to the left of the button.
+    fn finish(&mut self) {
+        // Hands are only shown when the board has been dealt, otherwise everyone else folded.
+        let scores: Vec<Option<Score>> = self.table.players().iter().map(|player| {
+            if player.folded || self.board.len() < 5 {
+                return None;
+            }
+
+            let mut cards = player.cards.to_vec();
+            cards.extend_from_slice(&self.board);
+            Some(PokerHand::best_of(&cards).unwrap().score)
+        }).collect();
+
+        self.table.finish(&scores, self.button);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cards::parse_cards;
+    use crate::table::testing::{play, stacked};
+
+    const STAKES: Stakes = Stakes { small_blind: 5, big_blind: 10, ante: 0 };
+
+    #[test]
+    fn blinds_and_first_to_act() {
+        let hand = Hand::new(&[100, 100, 100, 100], 1, STAKES, Deck::new()).unwrap();
+        assert_eq!(hand.blinds(), (2, 3));
+        assert_eq!(hand.to_act(), Some(0));
+        assert_eq!(hand.players()[2].stack, 95);
+        assert_eq!(hand.players()[3].bet, 10);
+        assert_eq!(hand.pot(), 15);
+        assert_eq!(hand.street(), Street::Preflop);
+        assert_eq!(hand.legal_actions(), vec![Legal::Fold, Legal::Call(10), Legal::Raise{ min: 20, max: 100 }]);
+    }
+
+    #[test]
+    fn heads_up_button_is_the_small_blind() {
+        let mut hand = Hand::new(&[100, 100], 0, STAKES, Deck::new()).unwrap();
+        assert_eq!(hand.blinds(), (0, 1));
+        assert_eq!(hand.to_act(), Some(0));
+
+        play(&mut hand, &[Action::Call, Action::Check]);
+        assert_eq!(hand.street(), Street::Flop);
+        assert_eq!(hand.board().len(), 3);
+        assert_eq!(hand.to_act(), Some(1));
+    }
+
+    #[test]
+    fn hole_cards_are_dealt_from_the_left_of_the_button() {
+        let hand = Hand::new(&[100, 100, 100], 2, STAKES, stacked("AsKsQsJsTs9s")).unwrap();
+        assert_eq!(hand.players()[0].cards, [Card::from_chars('A', 's').unwrap(), Card::from_chars('J', 's').unwrap()]);
+        assert_eq!(hand.players()[2].cards, [Card::from_chars('Q', 's').unwrap(), Card::from_chars('9', 's').unwrap()]);
+    }
+
+    #[test]
+    fn big_blind_has_the_option() {
+        let mut hand = Hand::new(&[100, 100, 100], 0, STAKES, Deck::new()).unwrap();
+        play(&mut hand, &[Action::Call, Action::Call]);
+        assert_eq!(hand.to_act(), Some(2));
+        assert_eq!(hand.legal_actions(), vec![Legal::Check, Legal::Raise{ min: 20, max: 100 }]);
+
+        play(&mut hand, &[Action::Raise(30), Action::Call, Action::Fold]);
+        assert_eq!(hand.street(), Street::Flop);
+        assert_eq!(hand.pot(), 70);
+        assert_eq!(hand.to_act(), Some(2));
+        assert_eq!(hand.legal_actions(), vec![Legal::Check, Legal::Bet{ min: 10, max: 70 }]);
+    }
+
+    #[test]
+    fn everyone_folds() {
+        let mut hand = Hand::new(&[100, 100, 100], 0, STAKES, Deck::new()).unwrap();
+        play(&mut hand, &[Action::Raise(25), Action::Fold, Action::Fold]);
+        assert!(hand.is_over());
+        assert_eq!(hand.to_act(), None);
+        assert_eq!(hand.winnings(), Some(&[25, 0, 0][..]));
+        assert_eq!(hand.players().iter().map(|player| player.stack).collect::<Vec<u64>>(), vec![115, 95, 90]);
+        assert!(hand.board().is_empty());
+        assert_eq!(hand.apply(Action::Check), Err(String::from("The hand is over.")));
+    }
+
+    #[test]
+    fn showdown_after_the_river() {
+        // Seat 1 gets aces, seat 0 gets kings and the board is no help.
+        let mut hand = Hand::new(&[100, 100], 0, STAKES, stacked("AhKhAdKd 2c 3s7c9d 2d Tc 2h Jh")).unwrap();
+        play(&mut hand, &[Action::Call, Action::Check]);
+        assert_eq!(hand.board(), &parse_cards("3s7c9d").unwrap()[..]);
+        play(&mut hand, &[Action::Bet(10), Action::Call]);
+        play(&mut hand, &[Action::Check, Action::Check]);
+        play(&mut hand, &[Action::Check, Action::Bet(20), Action::Call]);
+        assert!(hand.is_over());
+        assert_eq!(hand.board(), &parse_cards("3s7c9dTcJh").unwrap()[..]);
+        assert_eq!(hand.winnings(), Some(&[0, 80][..]));
+        assert_eq!(hand.players()[1].stack, 140);
+    }
+
+    #[test]
+    fn split_pot_odd_chip() {
+        // Both players play the board's straight, the odd chip from the ante goes left of the button.
+        let stakes = Stakes { small_blind: 5, big_blind: 10, ante: 1 };
+        let mut hand = Hand::new(&[100, 100, 100], 0, stakes, stacked("2c3c4c2d3d4d 5s 6h7h8h 5c 9s 5d Ts")).unwrap();
+        assert_eq!(hand.pot(), 18);
+        play(&mut hand, &[Action::Fold, Action::Call, Action::Check]);
+        for _ in 0..3 {
+            play(&mut hand, &[Action::Check, Action::Check]);
+        }
+        assert_eq!(hand.winnings(), Some(&[0, 12, 11][..]));
+    }
+
+    #[test]
+    fn all_in_runs_out_the_board() {
+        let mut hand = Hand::new(&[50, 200], 0, STAKES, stacked("AhKhAdKd")).unwrap();
+        play(&mut hand, &[Action::Raise(50)]);
+        assert_eq!(hand.legal_actions(), vec![Legal::Fold, Legal::Call(40)]);
+        play(&mut hand, &[Action::Call]);
+        assert!(hand.is_over());
+        assert_eq!(hand.board().len(), 5);
+        assert_eq!(hand.winnings().unwrap().iter().sum::<u64>(), 100);
+    }
+
+    #[test]
+    fn uncalled_bet_is_returned() {
+        let mut hand = Hand::new(&[50, 200], 0, STAKES, Deck::new()).unwrap();
+        play(&mut hand, &[Action::Call, Action::Raise(200)]);
+        assert_eq!(hand.legal_actions(), vec![Legal::Fold, Legal::Call(40)]);
+        play(&mut hand, &[Action::Call]);
+        assert!(hand.is_over());
+        assert_eq!(hand.players().iter().map(|player| player.invested).collect::<Vec<u64>>(), vec![50, 50]);
+        assert_eq!(hand.winnings().unwrap().iter().sum::<u64>(), 100);
+    }
+
+    #[test]
+    fn short_blind_is_all_in() {
+        let mut hand = Hand::new(&[100, 4], 0, STAKES, Deck::new()).unwrap();
+        assert!(hand.players()[1].is_all_in());
+        assert_eq!(hand.legal_actions(), vec![Legal::Fold, Legal::Call(5)]);
+        play(&mut hand, &[Action::Call]);
+        assert!(hand.is_over());
+        assert_eq!(hand.players()[0].invested, 4);
+    }
+
+    #[test]
+    fn fixed_limit_doubles_on_the_turn() {
+        let mut hand = Hand::with_limit(&[1000, 1000], 0, STAKES, Limit::FixedLimit{ max_bets: 4 }, Deck::new()).unwrap();
+        assert_eq!(hand.limit(), Limit::FixedLimit{ max_bets: 4 });
+        assert_eq!(hand.legal_actions(), vec![Legal::Fold, Legal::Call(5), Legal::Raise{ min: 20, max: 20 }]);
+        play(&mut hand, &[Action::Call, Action::Check]);
+        assert_eq!(hand.legal_actions(), vec![Legal::Check, Legal::Bet{ min: 10, max: 10 }]);
+        play(&mut hand, &[Action::Check, Action::Check]);
+        assert_eq!(hand.street(), Street::Turn);
+        assert_eq!(hand.legal_actions(), vec![Legal::Check, Legal::Bet{ min: 20, max: 20 }]);
+        play(&mut hand, &[Action::Bet(20)]);
+        assert_eq!(hand.legal_actions(), vec![Legal::Fold, Legal::Call(20), Legal::Raise{ min: 40, max: 40 }]);
+    }
+
+    #[test]
+    fn all_in_action() {
+        let mut hand = Hand::new(&[100, 60], 0, STAKES, Deck::new()).unwrap();
+        play(&mut hand, &[Action::AllIn]);
+        assert_eq!(hand.current_bet(), 100);
+        assert_eq!(hand.legal_actions(), vec![Legal::Fold, Legal::Call(50)]);
+        play(&mut hand, &[Action::AllIn]);
+        assert!(hand.is_over());
+        assert_eq!(hand.players().iter().map(|player| player.invested).collect::<Vec<u64>>(), vec![60, 60]);
+    }
+
+    #[test]
+    fn side_pot_for_the_short_stack() {
+        // Seat 0 has aces but only 30 chips, seat 1 has kings and seat 2 has queens.
+        let deck = stacked("AhKhQhAdKdQd 2c 3s7c9d 2d Tc 2h 4h");
+        let mut hand = Hand::new(&[30, 100, 100], 2, STAKES, deck).unwrap();
+        assert_eq!(hand.players()[0].cards[0], Card::from_chars('A', 'h').unwrap());
+        play(&mut hand, &[Action::AllIn, Action::AllIn, Action::Call]);
+        assert!(hand.is_over());
+        // The aces win 30 from each player and the kings win the rest.
+        assert_eq!(hand.winnings(), Some(&[90, 140, 0][..]));
+        assert_eq!(hand.players().iter().map(|player| player.stack).collect::<Vec<u64>>(), vec![90, 140, 0]);
+    }
+
+    #[test]
+    fn illegal_actions() {
+        let mut hand = Hand::new(&[100, 100, 100], 0, STAKES, Deck::new()).unwrap();
+        assert_eq!(hand.apply(Action::Check), Err(String::from("Seat 0 can't check.")));
+        assert_eq!(hand.apply(Action::Bet(20)), Err(String::from("Seat 0 can't bet 20.")));
+        assert_eq!(hand.apply(Action::Raise(15)), Err(String::from("Seat 0 can't raise to 15.")));
+        assert_eq!(hand.apply(Action::Raise(101)), Err(String::from("Seat 0 can't raise to 101.")));
+        assert_eq!(hand.to_act(), Some(0));
+    }
+
+    #[test]
+    fn bad_tables() {
+        assert_eq!(Hand::new(&[100], 0, STAKES, Deck::new()), Err(String::from("Required 2 to 22 players but found 1.")));
+        assert_eq!(Hand::new(&[100, 100], 2, STAKES, Deck::new()), Err(String::from("Seat 2 is not at the table.")));
+        assert_eq!(Hand::new(&[100, 0], 0, STAKES, Deck::new()), Err(String::from("Seat 1 has no chips.")));
+        let short = Deck::from_cards(parse_cards("AhKhQhJh").unwrap());
+        assert_eq!(Hand::new(&[100, 100], 0, STAKES, short), Err(String::from("Required 12 cards but only 4 remain.")));
+        let stakes = Stakes { small_blind: 10, big_blind: 5, ante: 0 };
+        assert_eq!(Hand::new(&[100, 100], 0, stakes, Deck::new()), Err(String::from("The big blind must be at least one chip and no smaller than the small blind.")));
+    }
+}
@@ -1,305 +1,621 @@
+pub mod betting;
 pub mod cards;
-
-use cards::Card;
+pub mod enumerate;
+pub mod equity;
+pub mod lowball;
+pub mod fast;
+pub mod hilo;
+pub mod holdem;
+pub mod omaha;
+pub mod pot;
+pub mod range;
+pub mod rng;
+pub mod rules;
+pub mod strength;
+pub mod stud;
+pub mod table;
+pub mod wild;
+
+use cards::{parse_cards, Card, Rank, Suit};
+use rules::RuleSet;
 
 use std::cmp::Ordering;
 use std::collections::BTreeMap;
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
 pub enum Score {
     HighCard{
-        card: Card,
+        card: Rank,
         // The kickers, from lowest to highest rank card.
-        kickers: [Card; 4]
+        kickers: [Rank; 4]
     },
     Pair{ 
-        card: Card,
+        card: Rank,
         // The kickers, from lowest to highest rank card.
-        kickers: [Card; 3]
+        kickers: [Rank; 3]
     },
     TwoPair{
-        low_pair: Card,
-        high_pair: Card,
-        kicker: Card
+        low_pair: Rank,
+        high_pair: Rank,
+        kicker: Rank
     },
     ThreeOfAKind {
-        card: Card,
-        high_kicker: Card,
-        low_kicker: Card
+        card: Rank,
+        high_kicker: Rank,
+        low_kicker: Rank
+    },
+    Straight(Rank),
+    Flush{
+        card: Rank,
+        // The kickers, from lowest to highest rank card.
+        kickers: [Rank; 4]
     },
-    Straight(Card),
     FullHouse{
-        pair: Card,
-        three_of_a_kind: Card
+        pair: Rank,
+        three_of_a_kind: Rank
     },
     FourOfAKind {
-        four_of_a_kind: Card,
-        kicker: Card
+        four_of_a_kind: Rank,
+        kicker: Rank
+    },
+    // A royal flush is the ace high straight flush.
+    StraightFlush(Rank),
+    // Only possible with wild cards or more than one deck.
+    FiveOfAKind(Rank)
+}
+
+/// The category of a score without the cards that break ties, from weakest to strongest.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash, PartialOrd, Ord)]
+pub enum Category {
+    HighCard,
+    Pair,
+    TwoPair,
+    ThreeOfAKind,
+    Straight,
+    Flush,
+    FullHouse,
+    FourOfAKind,
+    StraightFlush,
+    FiveOfAKind
+}
+
+impl Score {
+    pub fn category(&self) -> Category {
+        match self {
+            Score::HighCard{..} => Category::HighCard,
+            Score::Pair{..} => Category::Pair,
+            Score::TwoPair{..} => Category::TwoPair,
+            Score::ThreeOfAKind{..} => Category::ThreeOfAKind,
+            Score::Straight(_) => Category::Straight,
+            Score::Flush{..} => Category::Flush,
+            Score::FullHouse{..} => Category::FullHouse,
+            Score::FourOfAKind{..} => Category::FourOfAKind,
+            Score::StraightFlush(_) => Category::StraightFlush,
+            Score::FiveOfAKind(_) => Category::FiveOfAKind,
+        }
     }
 }
 
-#[derive(Debug, PartialEq)]
+/// Five cards to be scored.
+///
+/// A hand is either suited, built from real `Card`s, or suitless where only the ranks are known.
+#[derive(Debug, PartialEq, Eq)]
 pub struct PokerHand {
-    cards: [Card; 5]
+    ranks: [Rank; 5],
+    suits: Option<[Suit; 5]>
 }
 
 impl PokerHand {
+    /// Create a suited hand from five cards.
+    pub fn new(cards: [Card; 5]) -> PokerHand {
+        PokerHand {
+            ranks: [cards[0].rank, cards[1].rank, cards[2].rank, cards[3].rank, cards[4].rank],
+            suits: Some([cards[0].suit, cards[1].suit, cards[2].suit, cards[3].suit, cards[4].suit])
+        }
+    }
+
+    /// Create a suitless hand from five ranks, which can never be a flush.
+    pub fn from_ranks(ranks: [Rank; 5]) -> PokerHand {
+        PokerHand { ranks, suits: None }
+    }
+
+    /// Parse a hand from either five rank characters ("AAKKK") or five two character cards ("AsAhKsKdKc").
+    #[allow(clippy::should_implement_trait)]
     pub fn from_str(input:&str) -> Result<PokerHand, String> {
         // Rust has a very strict rules around indexing into strings.  Something something grapheme clusters and 
         // English is a pretty boring language as far as unicode goes.
         let chars:Vec<char> = input.chars().collect();
+        if chars.len() == 10 {
+            // Whitespace is skipped by parse_cards, so ten characters may still be fewer than five cards.
+            let cards = parse_cards(input)?;
+            if cards.len() != 5 {
+                return Err(format!("Required 5 cards but found {}.", cards.len()));
+            }
+            return Ok(PokerHand::new([cards[0], cards[1], cards[2], cards[3], cards[4]]));
+        }
+
         if chars.len() != 5 {
-            return Err(format!("Required 5 characters but found {}.", chars.len()));
+            return Err(format!("Required 5 or 10 characters but found {}.", chars.len()));
         }
 
-        Ok(PokerHand {
-            cards: [Card::from_char(chars[0])?, 
-                Card::from_char(chars[1])?, 
-                Card::from_char(chars[2])?, 
-                Card::from_char(chars[3])?, 
-                Card::from_char(chars[4])?]
-        })
+        Ok(PokerHand::from_ranks([Rank::from_char(chars[0])?, 
+            Rank::from_char(chars[1])?, 
+            Rank::from_char(chars[2])?, 
+            Rank::from_char(chars[3])?, 
+            Rank::from_char(chars[4])?]))
+    }
+
+    /// Find the best five card hand that can be made from 5, 6 or 7 cards, such as two hole cards and
+    /// a Texas Hold'em board.
+    ///
+    /// # Examples
+    /// ```
+    /// use poker::{PokerHand, Score};
+    /// use poker::cards::{parse_cards, Rank};
+    ///
+    /// let best = PokerHand::best_of(&parse_cards("AhKh QhJhTh2c3d").unwrap()).unwrap();
+    /// assert_eq!(best.score, Score::StraightFlush(Rank::Ace));
+    /// ```
+    pub fn best_of(cards: &[Card]) -> Result<BestHand, String> {
+        PokerHand::best_of_with(cards, &RuleSet::standard())
+    }
+
+    /// Find the best five card hand from 5, 6 or 7 cards under some rules, such as short deck.
+    pub fn best_of_with(cards: &[Card], rules: &RuleSet) -> Result<BestHand, String> {
+        if cards.len() < 5 || cards.len() > 7 {
+            return Err(format!("Required 5 to 7 cards but found {}.", cards.len()));
+        }
+
+        for (idx, card) in cards.iter().enumerate() {
+            if cards[..idx].contains(card) {
+                return Err(format!("Card '{}' appears more than once.", card));
+            }
+
+            if !rules.in_deck(card) {
+                return Err(format!("Card '{}' is not in the deck.", card));
+            }
+        }
+
+        let mut best: Option<BestHand> = None;
+        for_each_combination(cards.len(), 5, |idx| {
+            let hand = [cards[idx[0]], cards[idx[1]], cards[idx[2]], cards[idx[3]], cards[idx[4]]];
+            let score = PokerHand::new(hand).score_with(rules);
+            let better = match &best {
+                Some(current) => compare_hands_with(&score, &current.score, rules) == Ordering::Less,
+                None => true
+            };
+
+            if better {
+                best = Some(BestHand{ score, cards: hand });
+            }
+        });
+
+        Ok(best.unwrap())
     }
 
     pub fn score(&self) -> Score {
-        let mut cards: Vec<&Card> = Vec::new();
-        cards.extend(self.cards().iter());
-        cards.sort_by_key(|card| card_seq(card));
+        self.score_with(&RuleSet::standard())
+    }
+
+    /// Score the hand under some rules, which decide whether the ace plays high or low, which straights it
+    /// makes and whether straights and flushes count at all.
+    ///
+    /// # Examples
+    /// ```
+    /// use poker::rules::RuleSet;
+    /// use poker::{PokerHand, Score};
+    /// use poker::cards::Rank;
+    ///
+    /// let hand = PokerHand::from_str("A9876").unwrap();
+    /// assert_eq!(hand.score_with(&RuleSet::short_deck(false)), Score::Straight(Rank::Nine));
+    /// ```
+    pub fn score_with(&self, rules: &RuleSet) -> Score {
+        let seq = |card: &Rank| rules.seq(card);
+        let mut cards: Vec<Rank> = self.ranks.to_vec();
+        cards.sort_by_key(seq);
 
         // Count the number of each card.  
-        let mut counts: BTreeMap<&Card, usize> = BTreeMap::new();
+        let mut counts: BTreeMap<Rank, usize> = BTreeMap::new();
         for card in &cards {
-            counts.entry(card).and_modify(|e| *e += 1).or_insert(1);
+            counts.entry(*card).and_modify(|e| *e += 1).or_insert(1);
         }
 
-        let mut pairs:Vec<Card> = Vec::new();
-        let mut three_of_a_kind: Option<Card> = None;
-        let mut four_of_a_kind: Option<Card> = None;
+        let mut pairs:Vec<Rank> = Vec::new();
+        let mut three_of_a_kind: Option<Rank> = None;
+        let mut four_of_a_kind: Option<Rank> = None;
+        let mut five_of_a_kind: Option<Rank> = None;
         
         for (card, count) in counts.iter() {
-            if *count == 2 {
-                pairs.push((*card).clone());
-            } else if *count == 3 {
-                three_of_a_kind = Some((*card).clone());
-            } else if *count == 4 {
-                four_of_a_kind = Some((*card).clone());
-            } else if *count == 1 {
-                // don't care
-            } else {
-                panic!(format!("Unexpected card card {}", count));
+            match count {
+                1 => (), // don't care
+                2 => pairs.push(*card),
+                3 => three_of_a_kind = Some(*card),
+                4 => four_of_a_kind = Some(*card),
+                _ => five_of_a_kind = Some(*card)
             }
         }
 
-        pairs.sort_by_key(|card| card_seq(card));
+        pairs.sort_by_key(seq);
 
 
-        // Four of a kind
-        if four_of_a_kind.is_some() {
-            let mut kickers: Vec<Card> = Vec::new();
-            let c = four_of_a_kind.unwrap().clone();
-            for card in self.cards() {
-                if card != &c {
-                    kickers.push(card.clone());
-                }
-            }
+        let flush = match self.suits {
+            Some(suits) => rules.straights_and_flushes && suits.iter().all(|suit| *suit == suits[0]),
+            None => false
+        };
+        let straight = match rules.straights_and_flushes {
+            true => straight_high_card(&cards, rules),
+            false => None
+        };
 
-            return Score::FourOfAKind{ four_of_a_kind: c, kicker: kickers[0].clone() };
+        if let Some(c) = five_of_a_kind {
+            return Score::FiveOfAKind(c);
+        }
+
+        // Straight flush, including the five high and the royal flush.
+        if let (true, Some(high)) = (flush, straight) {
+            return Score::StraightFlush(high);
+        }
+
+        // Four of a kind
+        if let Some(c) = four_of_a_kind {
+            let kickers = self.kickers(&[c], rules);
+            return Score::FourOfAKind{ four_of_a_kind: c, kicker: kickers[0] };
         }
 
         // What's a full house?
-        if !pairs.is_empty() && three_of_a_kind.is_some() {
-            return Score::FullHouse{pair:pairs.last().unwrap().clone(), three_of_a_kind: three_of_a_kind.unwrap()};
+        if let (Some(pair), Some(toc)) = (pairs.last(), three_of_a_kind) {
+            return Score::FullHouse{pair: *pair, three_of_a_kind: toc};
         }
 
-        // Straight (Five Highh)
-        if cards[0] == &Card::Two &&
-            cards[1] == &Card::Three &&
-            cards[2] == &Card::Four &&
-            cards[3] == &Card::Five &&
-            cards[4] == &Card::Ace {
-            return Score::Straight(Card::Five);
+        if flush {
+            return Score::Flush{ card:cards[4],
+                kickers: [cards[0], cards[1], cards[2], cards[3]]
+            };
         }
 
-        // Straight (Ace High)
-        let first_card = card_seq(cards[0]);
-        if card_seq(cards[1]) == first_card + 1 &&
-            card_seq(cards[2]) == first_card + 2 &&
-            card_seq(cards[3]) == first_card + 3 &&
-            card_seq(cards[4]) == first_card + 4 {
-            return Score::Straight(cards[4].clone());
+        if let Some(high) = straight {
+            return Score::Straight(high);
         }
 
         // Two pair
         if pairs.len() == 2 {
-            let mut kickers: Vec<Card> = Vec::new();
-            for card in self.cards() {
-                if card != &pairs[0] && card != &pairs[1] {
-                    kickers.push(card.clone());
-                }
-            }
-
-            return Score::TwoPair{ low_pair:pairs[0].clone(), high_pair:pairs[1].clone(), kicker:kickers[0].clone()};
+            let kickers = self.kickers(&pairs, rules);
+            return Score::TwoPair{ low_pair:pairs[0], high_pair:pairs[1], kicker:kickers[0]};
         }
 
         // Three of a kind
-        if three_of_a_kind.is_some() { // todo if let
-            let mut kickers: Vec<Card> = Vec::new();
-            let toc = three_of_a_kind.unwrap();
-            for card in self.cards() {
-                if card != &toc {
-                    kickers.push(card.clone());
-                }
-            }
-
-            kickers.sort_by_key(|card| card_seq(card));
-            return Score::ThreeOfAKind{card:toc, low_kicker:kickers[0].clone(), high_kicker:kickers[1].clone()};
+        if let Some(toc) = three_of_a_kind {
+            let kickers = self.kickers(&[toc], rules);
+            return Score::ThreeOfAKind{card:toc, low_kicker:kickers[0], high_kicker:kickers[1]};
         }
 
         // Pairs
-        if !pairs.is_empty() {
-            let mut kickers: Vec<Card> = Vec::new();
-            for card in self.cards() {
-                if card != &pairs[0] {
-                    kickers.push(card.clone());
-                }
-            }
-
-            kickers.sort_by_key(|card| card_seq(card));
-
+        if let Some(pair) = pairs.last() {
+            let kickers = self.kickers(&[*pair], rules);
             return Score::Pair{
-                card:pairs.last().unwrap().clone(),
-                kickers: [kickers[0].clone(), kickers[1].clone(), kickers[2].clone()]
+                card: *pair,
+                kickers: [kickers[0], kickers[1], kickers[2]]
 
             };
         }
 
         // Final score, just the highest card.
-        Score::HighCard{ card:cards[4].clone(),
-            kickers: [cards[0].clone(), cards[1].clone(), cards[2].clone(), cards[3].clone() ]
+        Score::HighCard{ card:cards[4],
+            kickers: [cards[0], cards[1], cards[2], cards[3]]
+        }
+    }
+
+    /// The cards that are not one of `made`, from lowest to highest.
+    fn kickers(&self, made: &[Rank], rules: &RuleSet) -> Vec<Rank> {
+        let mut kickers: Vec<Rank> = self.ranks.iter()
+            .filter(|card| !made.contains(card))
+            .cloned()
+            .collect();
+
+        kickers.sort_by_key(|card| rules.seq(card));
+        kickers
+    }
+
+    pub fn ranks(&self) -> &[Rank; 5] {
+        &self.ranks
+    }
+
+    /// The cards of the hand, or `None` if the hand was created without suits.
+    pub fn cards(&self) -> Option<[Card; 5]> {
+        self.suits.map(|suits| {
+            [Card::new(self.ranks[0], suits[0]),
+                Card::new(self.ranks[1], suits[1]),
+                Card::new(self.ranks[2], suits[2]),
+                Card::new(self.ranks[3], suits[3]),
+                Card::new(self.ranks[4], suits[4])]
+        })
+    }
+}
+
+/// The best five cards found by `PokerHand::best_of`.
+#[derive(Debug, PartialEq, Clone)]
+pub struct BestHand {
+    pub score: Score,
+    pub cards: [Card; 5]
+}
+
+/// Call `f` with the indexes of every combination of `k` items chosen from `n`, in lexicographic order.
+pub(crate) fn for_each_combination<F: FnMut(&[usize])>(n: usize, k: usize, mut f: F) {
+    if k > n {
+        return;
+    }
+
+    let mut idx: Vec<usize> = (0..k).collect();
+    loop {
+        f(&idx);
+        if !next_combination(&mut idx, n) {
+            return;
+        }
+    }
+}
+
+/// Move `idx` on to the next combination of items chosen from `n`, returning false after the last one.
+pub(crate) fn next_combination(idx: &mut [usize], n: usize) -> bool {
+    // Move the right most index that can still move to the right and reset the ones after it.
+    let k = idx.len();
+    match (0..k).rev().find(|i| idx[*i] != i + n - k) {
+        Some(i) => {
+            idx[i] += 1;
+            for j in i + 1..k {
+                idx[j] = idx[j - 1] + 1;
+            }
+            true
+        },
+        None => false
+    }
+}
+
+/// Count each rank, ordered by how many there are and then from the highest `seq` to the lowest.
+pub(crate) fn group_ranks<F: Fn(&Rank) -> usize>(ranks: &[Rank], seq: F) -> Vec<(usize, Rank)> {
+    let mut groups: Vec<(usize, Rank)> = Vec::new();
+    for rank in ranks {
+        match groups.iter_mut().find(|(_, r)| r == rank) {
+            Some((count, _)) => *count += 1,
+            None => groups.push((1, *rank))
         }
     }
+    groups.sort_by(|left, right| right.0.cmp(&left.0).then(seq(&right.1).cmp(&seq(&left.1))));
+    groups
+}
 
-    pub fn cards(&self) -> &[Card; 5] {
-        &self.cards
+/// The high card of a straight made from `cards`, which must be sorted by `RuleSet::seq`.
+fn straight_high_card(cards: &[Rank], rules: &RuleSet) -> Option<Rank> {
+    // Straight (Five Highh), or the ace with the four lowest ranks of a shorter deck.
+    let low = card_seq(&rules.low_rank);
+    if rules.ace_high && rules.ace_low_straight && cards[4] == Rank::Ace && (0..4).all(|idx| card_seq(&cards[idx]) == low + idx) {
+        return Some(cards[3]);
     }
+
+    // Straight (Ace High)
+    let first_card = rules.seq(&cards[0]);
+    if (1..5).all(|idx| rules.seq(&cards[idx]) == first_card + idx) {
+        return Some(cards[4]);
+    }
+
+    None
 }
 
 /**
  * Return an integer that represents the sequence of a card as
  * played in Poker.
  */
-fn card_seq(card: &Card) -> usize {
+pub(crate) fn card_seq(card: &Rank) -> usize {
     match card {
-        Card::Two => 2,
-        Card::Three => 3,
-        Card::Four => 4,
-        Card::Five => 5,
-        Card::Six => 6,
-        Card::Seven => 7,
-        Card::Eight => 8,
-        Card::Nine => 9,
-        Card::Ten => 10,
-        Card::Jack => 11,
-        Card::Queen => 12,
-        Card::King => 13,
-        Card::Ace => 14,
+        Rank::Two => 2,
+        Rank::Three => 3,
+        Rank::Four => 4,
+        Rank::Five => 5,
+        Rank::Six => 6,
+        Rank::Seven => 7,
+        Rank::Eight => 8,
+        Rank::Nine => 9,
+        Rank::Ten => 10,
+        Rank::Jack => 11,
+        Rank::Queen => 12,
+        Rank::King => 13,
+        Rank::Ace => 14,
         
     }
 }
 
-fn score_seq(score:&Score) -> usize {
-    match score {
-        Score::HighCard{..} => 1,
-        Score::Pair{..} => 2,
-        Score::TwoPair{..} => 3,
-        Score::ThreeOfAKind{..} => 4,
-        Score::Straight(_) => 5, 
-        Score::FullHouse{..} => 6,
-        Score::FourOfAKind{..} => 7,
+fn cmp_cards(left_card:&Rank, right_card:&Rank, rules: &RuleSet) -> Ordering {
+    rules.seq(right_card).cmp(&rules.seq(left_card))
+}
+
+/// Compare kickers, which are sorted from lowest to highest, starting with the highest kicker.  Kickers are
+/// always equal when the rules ignore them.
+fn cmp_kickers(left_kickers:&[Rank], right_kickers:&[Rank], rules: &RuleSet) -> Ordering {
+    if !rules.kickers {
+        return Ordering::Equal;
+    }
+
+    for (left_card, right_card) in left_kickers.iter().rev().zip(right_kickers.iter().rev()) {
+        match cmp_cards(left_card, right_card, rules) {
+            Ordering::Equal => (),
+            less_or_greater => return less_or_greater
+        }
+    }
+
+    Ordering::Equal
+}
+
+/// Scores are ordered by strength, the stronger hand is `Greater`.
+///
+/// # Examples
+/// ```
+/// use poker::PokerHand;
+///
+/// let full_house = PokerHand::from_str("AAKKK").unwrap().score();
+/// let straight = PokerHand::from_str("23456").unwrap().score();
+/// assert!(full_house > straight);
+/// ```
+impl Ord for Score {
+    fn cmp(&self, other: &Score) -> Ordering {
+        // compare_hands puts the winning hand first.
+        compare_hands(other, self)
+    }
+}
+
+impl PartialOrd for Score {
+    fn partial_cmp(&self, other: &Score) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Hands are ordered by their score, the stronger hand is `Greater`.
+///
+/// Hands with the same score are still ordered by their cards so that the ordering agrees with `Eq`,
+/// compare the scores to find a split pot.
+impl Ord for PokerHand {
+    fn cmp(&self, other: &PokerHand) -> Ordering {
+        match self.score().cmp(&other.score()) {
+            Ordering::Equal => (self.ranks, self.suits).cmp(&(other.ranks, other.suits)),
+            less_or_greater => less_or_greater
+        }
+    }
+}
+
+impl PartialOrd for PokerHand {
+    fn partial_cmp(&self, other: &PokerHand) -> Option<Ordering> {
+        Some(self.cmp(other))
     }
 }
 
+/// Rank any number of hands from best to worst, grouping hands that tie.
+///
+/// Each group holds the indexes of the hands in `scores`, in order.  This works for anything ordered with the
+/// stronger hand `Greater`, such as a `Score` or a `strength::HandRank`.
+///
+/// # Examples
+/// ```
+/// use poker::{rank_hands, PokerHand};
+///
+/// let scores: Vec<_> = ["23456", "KA225", "TT8A9", "TTA89"].iter()
+///     .map(|hand| PokerHand::from_str(hand).unwrap().score())
+///     .collect();
+/// assert_eq!(rank_hands(&scores), vec![vec![0], vec![2, 3], vec![1]]);
+/// ```
+pub fn rank_hands<T: Ord>(scores: &[T]) -> Vec<Vec<usize>> {
+    let mut order: Vec<usize> = (0..scores.len()).collect();
+    order.sort_by(|left, right| scores[*right].cmp(&scores[*left]).then(left.cmp(right)));
+
+    let mut groups: Vec<Vec<usize>> = Vec::new();
+    for idx in order {
+        match groups.last_mut() {
+            Some(group) if scores[group[0]] == scores[idx] => group.push(idx),
+            _ => groups.push(vec![idx])
+        }
+    }
 
-fn cmp_cards(left_card:&Card, right_card:&Card) -> Ordering {
-    card_seq(&right_card).cmp(&card_seq(&left_card))
+    groups
+}
+
+/// The indexes of the hands that win a showdown, more than one when the pot is split.
+///
+/// # Examples
+/// ```
+/// use poker::{showdown, PokerHand};
+///
+/// let scores: Vec<_> = ["QQ2AT", "QQT2J", "QQA2T"].iter()
+///     .map(|hand| PokerHand::from_str(hand).unwrap().score())
+///     .collect();
+/// assert_eq!(showdown(&scores), vec![0, 2]);
+/// ```
+pub fn showdown<T: Ord>(scores: &[T]) -> Vec<usize> {
+    rank_hands(scores).into_iter().next().unwrap_or_default()
 }
 
 /** Compare two scored poker hands.
- * 
+ *
+ * The winning hand is ordered first, so `Less` means the left hand wins.  This is the reverse of the
+ * `Ord` implementation on `Score` and is kept for existing callers.
  */
 pub fn compare_hands(left_score:&Score, right_score:&Score) -> Ordering {
+    compare_hands_with(left_score, right_score, &RuleSet::standard())
+}
+
+/// Compare two scores of the same category, the winning hand is ordered first.
+fn compare_category(left_score:&Score, right_score:&Score, rules: &RuleSet) -> Ordering {
     // Compare the score of a poker hand.  There are rules that need to be applied if the score is the same
     // for each hand.   
     match (left_score, right_score) {
-        (Score::HighCard{card:left_card, kickers: left_kickers}, Score::HighCard{card:right_card, kickers: right_kickers}) => {
-            match cmp_cards(left_card, right_card) {
-                Ordering:: Equal => match cmp_cards(&left_kickers[0], &right_kickers[0]) {
-                    Ordering:: Equal => match cmp_cards(&left_kickers[1], &right_kickers[1]) {
-                        Ordering:: Equal => match cmp_cards(&left_kickers[2], &right_kickers[2]) {
-                            Ordering:: Equal => cmp_cards(&left_kickers[3], &right_kickers[3]),
-                            less_or_greater => less_or_greater
-                        },
-                        less_or_greater => less_or_greater
-                    },
-                    less_or_greater => less_or_greater
-                },
+        (Score::HighCard{card:left_card, kickers: left_kickers}, Score::HighCard{card:right_card, kickers: right_kickers}) |
+            (Score::Flush{card:left_card, kickers: left_kickers}, Score::Flush{card:right_card, kickers: right_kickers}) => {
+            match cmp_cards(left_card, right_card, rules) {
+                Ordering::Equal => cmp_kickers(left_kickers, right_kickers, rules),
                 less_or_greater => less_or_greater
             }
         },
         (Score::Pair{card:left_card, kickers: left_kickers}, Score::Pair{card:right_card, kickers: right_kickers}) => {
-            match cmp_cards(left_card, right_card) {
-                Ordering:: Equal => match cmp_cards(&left_kickers[0], &right_kickers[0]) {
-                    Ordering:: Equal => match cmp_cards(&left_kickers[1], &right_kickers[1]) {
-                        Ordering:: Equal => cmp_cards(&left_kickers[2], &right_kickers[2]),
-                        less_or_greater => less_or_greater
-                    },
-                    less_or_greater => less_or_greater
-                },
+            match cmp_cards(left_card, right_card, rules) {
+                Ordering::Equal => cmp_kickers(left_kickers, right_kickers, rules),
                 less_or_greater => less_or_greater
             }
         },
         (Score::TwoPair{low_pair:left_low_pair, high_pair:left_high_pair, kicker:left_kicker}, 
             Score::TwoPair{low_pair:right_low_pair, high_pair:right_high_pair, kicker:right_kicker}) => {
-            match cmp_cards(left_high_pair, right_high_pair) {
+            match cmp_cards(left_high_pair, right_high_pair, rules) {
                 Ordering::Less => Ordering::Less,
                 Ordering::Greater => Ordering::Greater,
-                Ordering::Equal => match cmp_cards(left_low_pair, right_low_pair) {
+                Ordering::Equal => match cmp_cards(left_low_pair, right_low_pair, rules) {
                     Ordering::Less => Ordering::Less,
                     Ordering::Greater => Ordering::Greater,
-                    Ordering::Equal => cmp_cards(left_kicker, right_kicker)
+                    Ordering::Equal => cmp_kickers(&[*left_kicker], &[*right_kicker], rules)
                 }
             }
         },
         (Score::ThreeOfAKind{card:left_card, high_kicker:left_high_kicker, low_kicker:left_low_kicker}, 
             Score::ThreeOfAKind{card:right_card, high_kicker:right_high_kicker, low_kicker:right_low_kicker}) => {
-            match cmp_cards(left_card, right_card) {
+            match cmp_cards(left_card, right_card, rules) {
                 Ordering::Equal => 
-                    match cmp_cards(left_high_kicker, right_high_kicker) {
-                        Ordering::Equal => cmp_cards(left_low_kicker, right_low_kicker),
-                        less_or_greater => less_or_greater
-                    },
+                    cmp_kickers(&[*left_low_kicker, *left_high_kicker], &[*right_low_kicker, *right_high_kicker], rules),
                 less_or_greater => less_or_greater
                 
             }
         },
-        (Score::Straight(left_card), Score::Straight(right_card)) => {
-            cmp_cards(left_card, right_card)
+        (Score::Straight(left_card), Score::Straight(right_card)) |
+            (Score::StraightFlush(left_card), Score::StraightFlush(right_card)) |
+            (Score::FiveOfAKind(left_card), Score::FiveOfAKind(right_card)) => {
+            cmp_cards(left_card, right_card, rules)
         },
         (Score::FullHouse{three_of_a_kind:left_three_of_a_kind, pair:left_pair}, Score::FullHouse{three_of_a_kind:right_three_of_a_kind, pair: right_pair}) => {
-            match cmp_cards(left_three_of_a_kind, right_three_of_a_kind) {
-                Ordering::Equal => cmp_cards(left_pair, right_pair),
+            match cmp_cards(left_three_of_a_kind, right_three_of_a_kind, rules) {
+                Ordering::Equal => cmp_cards(left_pair, right_pair, rules),
                 less_or_greater => less_or_greater
             }
         },
         (Score::FourOfAKind{four_of_a_kind:left_four_of_a_kind, kicker:left_kicker}, Score::FourOfAKind{four_of_a_kind: right_four_of_a_kind, kicker: right_kicker}) => {
-            match cmp_cards(left_four_of_a_kind, right_four_of_a_kind) {
-                Ordering::Equal => cmp_cards(left_kicker, right_kicker),
+            match cmp_cards(left_four_of_a_kind, right_four_of_a_kind, rules) {
+                Ordering::Equal => cmp_kickers(&[*left_kicker], &[*right_kicker], rules),
                 less_or_greater => less_or_greater
             }
         }
 
         // In the simple case then just compare the sequences of the scores themselves.
-        (left, right) => { score_seq(&right).cmp(&score_seq(&left)) },
+        (left, right) => { rules.cmp_categories(right.category(), left.category()) },
+    }
+}
+
+/// Compare two scored hands under some rules, such as short deck where a flush beats a full house.
+///
+/// Like `compare_hands`, the winning hand is ordered first so `Less` means the left hand wins.  Both scores
+/// should come from `PokerHand::score_with` using the same rules.
+pub fn compare_hands_with(left_score: &Score, right_score: &Score, rules: &RuleSet) -> Ordering {
+    let ordering = match rules.cmp_categories(right_score.category(), left_score.category()) {
+        Ordering::Equal => compare_category(left_score, right_score, rules),
+        less_or_greater => less_or_greater
+    };
+
+    // In lowball the worst high hand wins.
+    if rules.lowball {
+        ordering.reverse()
+    } else {
+        ordering
     }
 }
 
@@ -309,65 +625,310 @@ mod tests {
 
     #[test]
     fn score_pair() {
-        assert_eq!(PokerHand::from_str("23427").unwrap().score(), Score::Pair{ card:Card::Two, kickers:[Card::Three, Card::Four, Card::Seven]});
+        assert_eq!(PokerHand::from_str("23427").unwrap().score(), Score::Pair{ card:Rank::Two, kickers:[Rank::Three, Rank::Four, Rank::Seven]});
     }
 
     #[test]
     fn score_two_pair() {
-        assert_eq!(PokerHand::from_str("3434A").unwrap().score(), Score::TwoPair{low_pair:Card::Three, high_pair:Card::Four, kicker:Card::Ace});
+        assert_eq!(PokerHand::from_str("3434A").unwrap().score(), Score::TwoPair{low_pair:Rank::Three, high_pair:Rank::Four, kicker:Rank::Ace});
     }
 
     #[test]
     fn score_ace_high_straight() {
-       assert_eq!(PokerHand::from_str("TJQKA").unwrap().score(), Score::Straight(Card::Ace));
+       assert_eq!(PokerHand::from_str("TJQKA").unwrap().score(), Score::Straight(Rank::Ace));
     }
 
     #[test]
     fn score_five_high_straight() {
-       assert_eq!(PokerHand::from_str("A2345").unwrap().score(), Score::Straight(Card::Five));
+       assert_eq!(PokerHand::from_str("A2345").unwrap().score(), Score::Straight(Rank::Five));
     }
     
     #[test]
     fn score_high_card() {
-        assert_eq!(PokerHand::from_str("23457").unwrap().score(), Score::HighCard{card:Card::Seven, kickers:[Card::Two, Card::Three, Card::Four, Card::Five]});
+        assert_eq!(PokerHand::from_str("23457").unwrap().score(), Score::HighCard{card:Rank::Seven, kickers:[Rank::Two, Rank::Three, Rank::Four, Rank::Five]});
     }
 
     #[test]
     fn score_high_card_other_order() {
-        assert_eq!(PokerHand::from_str("75432").unwrap().score(), Score::HighCard{card:Card::Seven, kickers:[Card::Two, Card::Three, Card::Four, Card::Five]});
+        assert_eq!(PokerHand::from_str("75432").unwrap().score(), Score::HighCard{card:Rank::Seven, kickers:[Rank::Two, Rank::Three, Rank::Four, Rank::Five]});
     }
     
     #[test]
     fn score_high_card_ace() {
-        assert_eq!(PokerHand::from_str("A235K").unwrap().score(), Score::HighCard{card:Card::Ace, kickers:[Card::Two, Card::Three, Card::Five, Card::King]});
+        assert_eq!(PokerHand::from_str("A235K").unwrap().score(), Score::HighCard{card:Rank::Ace, kickers:[Rank::Two, Rank::Three, Rank::Five, Rank::King]});
+    }
+
+    #[test]
+    fn score_flush() {
+        assert_eq!(PokerHand::from_str("2h9hKh4h7h").unwrap().score(), Score::Flush{card:Rank::King, kickers:[Rank::Two, Rank::Four, Rank::Seven, Rank::Nine]});
+    }
+
+    #[test]
+    fn score_suitless_is_never_a_flush() {
+        assert_eq!(PokerHand::from_str("29K47").unwrap().score(), Score::HighCard{card:Rank::King, kickers:[Rank::Two, Rank::Four, Rank::Seven, Rank::Nine]});
+    }
+
+    #[test]
+    fn score_straight_flush() {
+        assert_eq!(PokerHand::from_str("9c8c7c6c5c").unwrap().score(), Score::StraightFlush(Rank::Nine));
+    }
+
+    #[test]
+    fn score_five_high_straight_flush() {
+        assert_eq!(PokerHand::from_str("Ad2d3d4d5d").unwrap().score(), Score::StraightFlush(Rank::Five));
+    }
+
+    #[test]
+    fn score_royal_flush() {
+        assert_eq!(PokerHand::from_str("TsJsQsKsAs").unwrap().score(), Score::StraightFlush(Rank::Ace));
     }
 
     #[test]
     fn score_four_of_a_king() {
-        assert_eq!(PokerHand::from_str("A2222").unwrap().score(), Score::FourOfAKind{four_of_a_kind:Card::Two, kicker:Card::Ace});
+        assert_eq!(PokerHand::from_str("A2222").unwrap().score(), Score::FourOfAKind{four_of_a_kind:Rank::Two, kicker:Rank::Ace});
     }
 
 
+    #[test]
+    fn score_five_of_a_kind() {
+        assert_eq!(PokerHand::from_str("KKKKK").unwrap().score(), Score::FiveOfAKind(Rank::King));
+    }
+
+    #[test]
+    fn compare_five_of_a_kind() {
+        let kings = PokerHand::from_str("KKKKK").unwrap().score();
+        let aces = PokerHand::from_str("AAAAA").unwrap().score();
+        let four_of_a_kind = PokerHand::from_str("AAAAK").unwrap().score();
+        let royal = PokerHand::from_str("TsJsQsKsAs").unwrap().score();
+
+        assert_eq!(compare_hands(&aces, &kings), Ordering::Less);
+        assert_eq!(compare_hands(&kings, &kings), Ordering::Equal);
+        assert_eq!(compare_hands(&kings, &four_of_a_kind), Ordering::Less);
+        assert_eq!(compare_hands(&kings, &royal), Ordering::Less);
+    }
+
+    #[test]
+    fn compare_flush_tie_break() {
+        let left = PokerHand::from_str("AhJh9h4h2h").unwrap().score();
+        let right = PokerHand::from_str("AcJc9c5c2c").unwrap().score();
+        assert_eq!(compare_hands(&left, &right), Ordering::Greater);
+        assert_eq!(compare_hands(&right, &left), Ordering::Less);
+        assert_eq!(compare_hands(&left, &PokerHand::from_str("AsJs9s4s2s").unwrap().score()), Ordering::Equal);
+    }
+
+    #[test]
+    fn compare_high_card_starts_with_highest_kicker() {
+        let left = PokerHand::from_str("AK532").unwrap().score();
+        let right = PokerHand::from_str("AQJT8").unwrap().score();
+        assert_eq!(compare_hands(&left, &right), Ordering::Less);
+    }
+
+    #[test]
+    fn compare_flush_categories() {
+        let straight = PokerHand::from_str("TJQKA").unwrap().score();
+        let flush = PokerHand::from_str("2h9hKh4h7h").unwrap().score();
+        let full_house = PokerHand::from_str("AAKKK").unwrap().score();
+        let four_of_a_kind = PokerHand::from_str("AAAAK").unwrap().score();
+        let five_high = PokerHand::from_str("Ad2d3d4d5d").unwrap().score();
+        let royal = PokerHand::from_str("TsJsQsKsAs").unwrap().score();
+
+        assert_eq!(compare_hands(&flush, &straight), Ordering::Less);
+        assert_eq!(compare_hands(&flush, &full_house), Ordering::Greater);
+        assert_eq!(compare_hands(&five_high, &four_of_a_kind), Ordering::Less);
+        assert_eq!(compare_hands(&royal, &five_high), Ordering::Less);
+    }
+
+    #[test]
+    fn best_of_seven_uses_the_board() {
+        let best = PokerHand::best_of(&parse_cards("2c7d AsKsQsJsTs").unwrap()).unwrap();
+        assert_eq!(best.score, Score::StraightFlush(Rank::Ace));
+        assert_eq!(best.cards, PokerHand::from_str("AsKsQsJsTs").unwrap().cards().unwrap());
+    }
+
+    #[test]
+    fn best_of_seven_picks_best_kickers() {
+        let best = PokerHand::best_of(&parse_cards("AhQc AdKs9h5c2d").unwrap()).unwrap();
+        assert_eq!(best.score, Score::Pair{card:Rank::Ace, kickers:[Rank::Nine, Rank::Queen, Rank::King]});
+    }
+
+    #[test]
+    fn best_of_seven_flush_over_straight() {
+        let best = PokerHand::best_of(&parse_cards("Ah4h 5h6c7h8d2h").unwrap()).unwrap();
+        assert_eq!(best.score, Score::Flush{card:Rank::Ace, kickers:[Rank::Two, Rank::Four, Rank::Five, Rank::Seven]});
+    }
+
+    #[test]
+    fn best_of_six() {
+        let best = PokerHand::best_of(&parse_cards("KhKd KcKs2c3c").unwrap()).unwrap();
+        assert_eq!(best.score, Score::FourOfAKind{four_of_a_kind:Rank::King, kicker:Rank::Three});
+    }
+
+    #[test]
+    fn best_of_five_is_the_hand() {
+        let best = PokerHand::best_of(&parse_cards("9c8c7c6c5c").unwrap()).unwrap();
+        assert_eq!(best.score, Score::StraightFlush(Rank::Nine));
+    }
+
+    #[test]
+    fn best_of_wrong_count() {
+        assert_eq!(PokerHand::best_of(&parse_cards("AhKh").unwrap()), Err(String::from("Required 5 to 7 cards but found 2.")));
+        assert_eq!(PokerHand::best_of(&Card::all()[..8]), Err(String::from("Required 5 to 7 cards but found 8.")));
+    }
+
+    #[test]
+    fn best_of_duplicate_card() {
+        let mut cards = parse_cards("AhKhQhJh").unwrap();
+        cards.push(cards[0]);
+        assert_eq!(PokerHand::best_of(&cards), Err(String::from("Card 'Ah' appears more than once.")));
+    }
+
+    #[test]
+    fn combinations_of_seven() {
+        let mut count = 0;
+        for_each_combination(7, 5, |_| count += 1);
+        assert_eq!(count, 21);
+
+        let collect = |n, k| {
+            let mut all: Vec<Vec<usize>> = Vec::new();
+            for_each_combination(n, k, |idx| all.push(idx.to_vec()));
+            all
+        };
+        assert_eq!(collect(3, 2), vec![vec![0, 1], vec![0, 2], vec![1, 2]]);
+        assert_eq!(collect(3, 3), vec![vec![0, 1, 2]]);
+        assert_eq!(collect(3, 0), vec![Vec::<usize>::new()]);
+        assert!(collect(2, 3).is_empty());
+    }
+
+    #[test]
+    fn score_ord_stronger_is_greater() {
+        let pair = PokerHand::from_str("23427").unwrap().score();
+        let two_pair = PokerHand::from_str("3434A").unwrap().score();
+        let flush = PokerHand::from_str("2h9hKh4h7h").unwrap().score();
+
+        assert!(two_pair > pair);
+        assert!(flush > two_pair);
+        assert_eq!(pair.cmp(&two_pair), compare_hands(&two_pair, &pair));
+        assert_eq!(pair.cmp(&pair.clone()), Ordering::Equal);
+    }
+
+    #[test]
+    fn score_sort_and_max() {
+        let mut scores: Vec<Score> = ["AAKKK", "23456", "KA225", "A2345", "TJQKA"].iter()
+            .map(|hand| PokerHand::from_str(hand).unwrap().score())
+            .collect();
+        scores.sort();
+
+        assert_eq!(scores, vec![
+            Score::Pair{card:Rank::Two, kickers:[Rank::Five, Rank::King, Rank::Ace]},
+            Score::Straight(Rank::Five),
+            Score::Straight(Rank::Six),
+            Score::Straight(Rank::Ace),
+            Score::FullHouse{pair:Rank::Ace, three_of_a_kind:Rank::King}
+        ]);
+        assert_eq!(scores.iter().max(), Some(&Score::FullHouse{pair:Rank::Ace, three_of_a_kind:Rank::King}));
+    }
+
+    #[test]
+    fn poker_hand_ord() {
+        let mut hands: Vec<PokerHand> = ["TT8A9", "AAKKK", "TTA89", "KKKKK"].iter()
+            .map(|hand| PokerHand::from_str(hand).unwrap())
+            .collect();
+        hands.sort();
+
+        assert_eq!(hands.last(), Some(&PokerHand::from_str("KKKKK").unwrap()));
+        assert_eq!(hands[0].score(), hands[1].score());
+        assert_ne!(hands[0], hands[1]);
+        assert_eq!(hands[0].cmp(&hands[1]), Ordering::Less);
+    }
+
+    #[test]
+    fn score_in_btree_map() {
+        let mut counts: BTreeMap<Score, usize> = BTreeMap::new();
+        for hand in ["TT8A9", "TTA89", "23456"].iter() {
+            *counts.entry(PokerHand::from_str(hand).unwrap().score()).or_insert(0) += 1;
+        }
+
+        assert_eq!(counts.len(), 2);
+        assert_eq!(counts.keys().next_back(), Some(&Score::Straight(Rank::Six)));
+    }
+
+    #[test]
+    fn showdown_single_winner() {
+        let scores: Vec<Score> = ["23427", "AAKKK", "3434A"].iter().map(|hand| PokerHand::from_str(hand).unwrap().score()).collect();
+        assert_eq!(showdown(&scores), vec![1]);
+        assert_eq!(rank_hands(&scores), vec![vec![1], vec![2], vec![0]]);
+    }
+
+    #[test]
+    fn showdown_split_between_many() {
+        let scores: Vec<Score> = ["TJQKA", "AKQJT", "23456", "KQJTA", "2h9hKh4h7h"].iter()
+            .map(|hand| PokerHand::from_str(hand).unwrap().score())
+            .collect();
+        assert_eq!(showdown(&scores), vec![4]);
+        assert_eq!(rank_hands(&scores), vec![vec![4], vec![0, 1, 3], vec![2]]);
+    }
+
+    #[test]
+    fn showdown_nobody() {
+        let scores: Vec<Score> = Vec::new();
+        assert_eq!(showdown(&scores), Vec::<usize>::new());
+        assert_eq!(rank_hands(&scores), Vec::<Vec<usize>>::new());
+    }
 
     #[test]
     fn hand_from_char_wrong_length() {
-        assert_eq!(PokerHand::from_str(&"TTTTTT"), Result::Err(String::from("Required 5 characters but found 6.")));
+        assert_eq!(PokerHand::from_str("TTTTTT"), Result::Err(String::from("Required 5 or 10 characters but found 6.")));
     }
 
     #[test]
     fn hand_from_char_bad_char() {
-        assert_eq!(PokerHand::from_str(&"TTTTX"), Result::Err(String::from("Character 'X' is not valid.")));
+        assert_eq!(PokerHand::from_str("TTTTX"), Result::Err(String::from("Character 'X' is not valid.")));
     }
 
     #[test]
     fn hand_from_char_ok() {
-        assert_eq!(PokerHand::from_str(&"23456"), 
+        assert_eq!(PokerHand::from_str("23456"), 
             Result::Ok(
                 PokerHand {
-                    cards: [Card::Two, Card::Three, Card::Four, Card::Five, Card::Six]
+                    ranks: [Rank::Two, Rank::Three, Rank::Four, Rank::Five, Rank::Six],
+                    suits: None
                 }
             )
         );
     }
 
+    #[test]
+    fn hand_from_str_suited() {
+        let hand = PokerHand::from_str("AsAhKsKdKc").unwrap();
+        assert_eq!(hand.ranks(), &[Rank::Ace, Rank::Ace, Rank::King, Rank::King, Rank::King]);
+        assert_eq!(hand.cards(), Some([
+            Card::new(Rank::Ace, Suit::Spades),
+            Card::new(Rank::Ace, Suit::Hearts),
+            Card::new(Rank::King, Suit::Spades),
+            Card::new(Rank::King, Suit::Diamonds),
+            Card::new(Rank::King, Suit::Clubs)]));
+        assert_eq!(hand.score(), Score::FullHouse{pair:Rank::Ace, three_of_a_kind:Rank::King});
+    }
+
+    #[test]
+    fn hand_from_str_suitless_has_no_cards() {
+        assert_eq!(PokerHand::from_str("AAKKK").unwrap().cards(), None);
+    }
+
+    #[test]
+    fn hand_from_str_duplicate_card() {
+        assert_eq!(PokerHand::from_str("AsAsKsKdKc"), Err(String::from("Card 'As' appears more than once.")));
+    }
+
+    #[test]
+    fn hand_from_str_bad_suit() {
+        assert_eq!(PokerHand::from_str("AsAxKsKdKc"), Err(String::from("Suit 'x' is not valid.")));
+    }
+
+    #[test]
+    fn hand_from_str_whitespace() {
+        assert_eq!(PokerHand::from_str("AsAh KsKd "), Err(String::from("Required 5 cards but found 4.")));
+        assert_eq!(PokerHand::from_str("  AsAhKs  "), Err(String::from("Required 5 cards but found 3.")));
+    }
+
 }
\ No newline at end of file
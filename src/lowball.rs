@@ -0,0 +1,323 @@
+//! # lowball
+//!
+//! `lowball` scores hands where the lowest hand wins.
+//!
+//! Ace-to-five, used by Razz, California lowball and the low half of split pot games, plays the ace low and
+//! ignores straights and flushes, so A-2-3-4-5 is the best hand.
+//!
+//! Deuce-to-seven, used by Kansas City lowball, plays the ace high and counts straights and flushes
+//! against you, so 7-5-4-3-2 of more than one suit is the best hand.
+
+use crate::cards::{Card, Rank};
+use crate::rules::RuleSet;
+use crate::{card_seq, compare_hands_with, for_each_combination, group_ranks, Category, PokerHand, Score};
+
+use std::cmp::Ordering;
+use std::fmt;
+
+/// The score of an ace-to-five low hand.
+///
+/// Scores are ordered by strength, like `Score`, so the better low hand is `Greater`.
+///
+/// # Examples
+/// ```
+/// use poker::PokerHand;
+/// use poker::lowball::ace_to_five;
+///
+/// let wheel = ace_to_five(PokerHand::from_str("A2345").unwrap().ranks());
+/// let six_four = ace_to_five(PokerHand::from_str("6432A").unwrap().ranks());
+/// assert!(wheel > six_four);
+/// ```
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+pub struct LowScore {
+    // Only the cards that pair count, a straight or flush is still a high card hand.
+    category: Category,
+    // Grouped by how many of each rank there are, then from highest to lowest with the ace low.
+    ranks: [Rank; 5]
+}
+
+impl LowScore {
+    /// How the hand pairs, a hand without pairs is a `HighCard` hand.
+    pub fn category(&self) -> Category {
+        self.category
+    }
+
+    /// The ranks from the most to the least important, the ace counts as one.
+    pub fn ranks(&self) -> &[Rank; 5] {
+        &self.ranks
+    }
+
+    /// The highest card of a hand without pairs, such as the eight of an eight low.
+    pub fn high_card(&self) -> Rank {
+        self.ranks[0]
+    }
+}
+
+impl Ord for LowScore {
+    fn cmp(&self, other: &LowScore) -> Ordering {
+        // Fewer pairs is better and then lower cards are better, so both comparisons are reversed.
+        match other.category.cmp(&self.category) {
+            Ordering::Equal => {
+                let left: Vec<usize> = self.ranks.iter().map(low_seq).collect();
+                let right: Vec<usize> = other.ranks.iter().map(low_seq).collect();
+                right.cmp(&left)
+            },
+            less_or_greater => less_or_greater
+        }
+    }
+}
+
+impl PartialOrd for LowScore {
+    fn partial_cmp(&self, other: &LowScore) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Written from the highest card, such as "7-5-4-3-A".
+impl fmt::Display for LowScore {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let ranks: Vec<String> = self.ranks.iter().map(|rank| rank.to_string()).collect();
+        write!(f, "{}", ranks.join("-"))
+    }
+}
+
+/// The best ace-to-five low made from some cards and the five cards that make it.
+#[derive(Debug, PartialEq, Clone)]
+pub struct BestLow {
+    pub score: LowScore,
+    pub cards: [Card; 5]
+}
+
+/// The sequence of a rank when the ace plays low.
+fn low_seq(rank: &Rank) -> usize {
+    match rank {
+        Rank::Ace => 1,
+        other => card_seq(other)
+    }
+}
+
+/// Score five ranks as an ace-to-five low hand.  Suits don't matter.
+pub fn ace_to_five(ranks: &[Rank; 5]) -> LowScore {
+    let groups = group_ranks(ranks, low_seq);
+
+    let category = match (groups[0].0, groups.len()) {
+        (5, _) => Category::FiveOfAKind,
+        (4, _) => Category::FourOfAKind,
+        (3, 2) => Category::FullHouse,
+        (3, _) => Category::ThreeOfAKind,
+        (2, 3) => Category::TwoPair,
+        (2, _) => Category::Pair,
+        _ => Category::HighCard
+    };
+
+    let mut ordered = [Rank::Ace; 5];
+    let mut idx = 0;
+    for (count, rank) in groups {
+        for _ in 0..count {
+            ordered[idx] = rank;
+            idx += 1;
+        }
+    }
+
+    LowScore { category, ranks: ordered }
+}
+
+/// Find the best ace-to-five low from 5 to 7 cards, such as a Razz hand.
+///
+/// # Examples
+/// ```
+/// use poker::cards::parse_cards;
+/// use poker::lowball::best_ace_to_five;
+///
+/// let best = best_ace_to_five(&parse_cards("AhAd 2c3c8s4hKd").unwrap()).unwrap();
+/// assert_eq!(best.score.to_string(), "8-4-3-2-A");
+/// ```
+pub fn best_ace_to_five(cards: &[Card]) -> Result<BestLow, String> {
+    if cards.len() < 5 || cards.len() > 7 {
+        return Err(format!("Required 5 to 7 cards but found {}.", cards.len()));
+    }
+
+    for (idx, card) in cards.iter().enumerate() {
+        if cards[..idx].contains(card) {
+            return Err(format!("Card '{}' appears more than once.", card));
+        }
+    }
+
+    let mut best: Option<BestLow> = None;
+    for_each_combination(cards.len(), 5, |idx| {
+        let hand = [cards[idx[0]], cards[idx[1]], cards[idx[2]], cards[idx[3]], cards[idx[4]]];
+        let score = ace_to_five(&hand.map(|card| card.rank));
+        let better = match &best {
+            Some(current) => score > current.score,
+            None => true
+        };
+
+        if better {
+            best = Some(BestLow { score, cards: hand });
+        }
+    });
+
+    Ok(best.unwrap())
+}
+
+/// The score of a deuce-to-seven low hand, the high hand `Score` turned upside down.
+///
+/// Scores are ordered by strength, so the better low hand, which is the worse high hand, is `Greater`.
+///
+/// # Examples
+/// ```
+/// use poker::PokerHand;
+/// use poker::lowball::deuce_to_seven;
+///
+/// let number_one = deuce_to_seven(&PokerHand::from_str("7c5d4h3s2c").unwrap());
+/// let wheel = deuce_to_seven(&PokerHand::from_str("Ac5d4h3s2c").unwrap());
+/// assert!(number_one > wheel);
+/// ```
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+pub struct DeuceToSevenScore(Score);
+
+impl DeuceToSevenScore {
+    /// The hand as it would be scored high, except that A-2-3-4-5 is an ace high hand.
+    pub fn high(&self) -> &Score {
+        &self.0
+    }
+}
+
+impl Ord for DeuceToSevenScore {
+    fn cmp(&self, other: &DeuceToSevenScore) -> Ordering {
+        // compare_hands_with puts the winning hand first.
+        compare_hands_with(&other.0, &self.0, &RuleSet::deuce_to_seven())
+    }
+}
+
+impl PartialOrd for DeuceToSevenScore {
+    fn partial_cmp(&self, other: &DeuceToSevenScore) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// The deuce-to-seven games, which rank hands the same way with `deuce_to_seven` and differ only in how often
+/// you draw.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum DeuceToSeven {
+    SingleDraw,
+    TripleDraw
+}
+
+impl DeuceToSeven {
+    /// The number of times each player may discard and draw.
+    pub fn draws(self) -> usize {
+        match self {
+            DeuceToSeven::SingleDraw => 1,
+            DeuceToSeven::TripleDraw => 3
+        }
+    }
+}
+
+/// Score a hand as a deuce-to-seven low hand, for either draw game.  A hand without suits can't be a flush.
+pub fn deuce_to_seven(hand: &PokerHand) -> DeuceToSevenScore {
+    DeuceToSevenScore(hand.score_with(&RuleSet::deuce_to_seven()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cards::parse_cards;
+    use crate::PokerHand;
+
+    fn low(hand: &str) -> LowScore {
+        ace_to_five(PokerHand::from_str(hand).unwrap().ranks())
+    }
+
+    #[test]
+    fn wheel_is_the_nut_low() {
+        assert_eq!(low("5432A").to_string(), "5-4-3-2-A");
+        assert_eq!(low("5432A").category(), Category::HighCard);
+        assert!(low("A2345") > low("A2346"));
+        assert!(low("A2345") > low("23456"));
+    }
+
+    #[test]
+    fn straights_and_flushes_do_not_count() {
+        assert_eq!(low("76543"), low("34567"));
+        assert_eq!(ace_to_five(PokerHand::from_str("7h6h5h4h3h").unwrap().ranks()), low("76543"));
+        assert!(low("76543") > low("87432"));
+    }
+
+    #[test]
+    fn compare_from_the_highest_card() {
+        assert!(low("87432") < low("86543"));
+        assert!(low("7532A") > low("7542A"));
+        assert_eq!(low("7532A").cmp(&low("A2357")), Ordering::Equal);
+        assert_eq!(low("8532A").high_card(), Rank::Eight);
+    }
+
+    #[test]
+    fn pairs_are_worse_than_any_high_card() {
+        assert!(low("KQJT9") > low("AA234"));
+        assert_eq!(low("AA234").category(), Category::Pair);
+        assert_eq!(low("AA234").ranks(), &[Rank::Ace, Rank::Ace, Rank::Four, Rank::Three, Rank::Two]);
+        assert!(low("AA234") > low("22A34"));
+        assert!(low("22A34") > low("AA22K"));
+        assert!(low("AA22K") > low("AAA23"));
+        assert!(low("AAA23") > low("AAA22"));
+        assert!(low("AAA22") > low("AAAA2"));
+        assert_eq!(low("AAA22").category(), Category::FullHouse);
+    }
+
+    #[test]
+    fn best_of_seven_razz() {
+        let best = best_ace_to_five(&parse_cards("KsKd 2c3c8s4hAd").unwrap()).unwrap();
+        assert_eq!(best.score, low("8432A"));
+
+        let paired = best_ace_to_five(&parse_cards("KsKd QcQh Js Jh Kc").unwrap()).unwrap();
+        assert_eq!(paired.score.category(), Category::TwoPair);
+        assert_eq!(paired.score.to_string(), "Q-Q-J-J-K");
+    }
+
+    fn deuce(hand: &str) -> DeuceToSevenScore {
+        deuce_to_seven(&PokerHand::from_str(hand).unwrap())
+    }
+
+    #[test]
+    fn seven_five_is_the_nuts() {
+        let nuts = deuce("7c5d4h3s2c");
+        assert_eq!(nuts.high(), &Score::HighCard{ card: Rank::Seven, kickers: [Rank::Two, Rank::Three, Rank::Four, Rank::Five] });
+        assert!(nuts > deuce("7c6d4h3s2c"));
+        assert!(nuts > deuce("8c5d4h3s2c"));
+        assert!(deuce("7c6d4h3s2c") > deuce("7c6d5h3s2c"));
+    }
+
+    #[test]
+    fn aces_are_high() {
+        assert_eq!(deuce("Ac5d4h3s2c").high().category(), Category::HighCard);
+        assert!(deuce("Kc5d4h3s2c") > deuce("Ac5d4h3s2c"));
+        assert!(deuce("Ac5d4h3s2c") > deuce("2c2d4h3s5c"));
+        assert!(deuce("AcAd4h3s5c") < deuce("KcKd4h3s5c"));
+    }
+
+    #[test]
+    fn straights_and_flushes_count_against_you() {
+        assert_eq!(deuce("6c5d4h3s2c").high(), &Score::Straight(Rank::Six));
+        assert!(deuce("KcQdJh9s8c") > deuce("6c5d4h3s2c"));
+        assert!(deuce("7c5c4c3c2c") > deuce("KcKdKhQsQc"));
+        assert!(deuce("7c5c4c3c2c") < deuce("AcAd4h3s5c"));
+        assert_eq!(deuce("7c5c4c3c2c").high().category(), Category::Flush);
+        // Without suits there is no flush.
+        assert_eq!(deuce("75432"), deuce("7c5d4h3s2c"));
+    }
+
+    #[test]
+    fn draws_per_game() {
+        assert_eq!(DeuceToSeven::SingleDraw.draws(), 1);
+        assert_eq!(DeuceToSeven::TripleDraw.draws(), 3);
+    }
+
+    #[test]
+    fn best_of_bad_input() {
+        assert_eq!(best_ace_to_five(&parse_cards("KsKd2c3c").unwrap()), Err(String::from("Required 5 to 7 cards but found 4.")));
+        let mut cards = parse_cards("KsKd2c3c5c").unwrap();
+        cards.push(cards[0]);
+        assert_eq!(best_ace_to_five(&cards), Err(String::from("Card 'Ks' appears more than once.")));
+    }
+}
@@ -0,0 +1,117 @@
+//! # omaha
+//!
+//! `omaha` scores Omaha hands, where a player must use exactly two of their hole cards and exactly
+//! three cards from the board.
+
+use crate::cards::Card;
+use crate::{compare_hands, for_each_combination, PokerHand, Score};
+
+use std::cmp::Ordering;
+
+/// The best hand an Omaha player can make and the cards that were used to make it.
+#[derive(Debug, PartialEq, Clone)]
+pub struct OmahaHand {
+    pub score: Score,
+    pub hole_cards: [Card; 2],
+    pub board_cards: [Card; 3]
+}
+
+/// Find the best hand using exactly two of the 4, 5 or 6 hole cards and exactly three of the 3 to 5 board cards.
+///
+/// # Examples
+/// ```
+/// use poker::omaha;
+/// use poker::Score;
+/// use poker::cards::{parse_cards, Rank};
+///
+/// // Four hearts on the board are no use with only one heart in hand.
+/// let hand = omaha::best_hand(&parse_cards("AhKsKdQc").unwrap(), &parse_cards("2h5h9hJh3c").unwrap()).unwrap();
+/// assert_eq!(hand.score, Score::Pair{card:Rank::King, kickers:[Rank::Five, Rank::Nine, Rank::Jack]});
+/// ```
+pub fn best_hand(hole_cards: &[Card], board: &[Card]) -> Result<OmahaHand, String> {
+    if hole_cards.len() < 4 || hole_cards.len() > 6 {
+        return Err(format!("Required 4 to 6 hole cards but found {}.", hole_cards.len()));
+    }
+
+    if board.len() < 3 || board.len() > 5 {
+        return Err(format!("Required 3 to 5 board cards but found {}.", board.len()));
+    }
+
+    let all: Vec<&Card> = hole_cards.iter().chain(board.iter()).collect();
+    for (idx, card) in all.iter().enumerate() {
+        if all[..idx].contains(card) {
+            return Err(format!("Card '{}' appears more than once.", card));
+        }
+    }
+
+    let mut best: Option<OmahaHand> = None;
+    for_each_combination(hole_cards.len(), 2, |hole_idx| {
+        for_each_combination(board.len(), 3, |board_idx| {
+            let hole = [hole_cards[hole_idx[0]], hole_cards[hole_idx[1]]];
+            let used = [board[board_idx[0]], board[board_idx[1]], board[board_idx[2]]];
+            let score = PokerHand::new([hole[0], hole[1], used[0], used[1], used[2]]).score();
+            let better = match &best {
+                Some(current) => compare_hands(&score, &current.score) == Ordering::Less,
+                None => true
+            };
+
+            if better {
+                best = Some(OmahaHand{ score, hole_cards: hole, board_cards: used });
+            }
+        });
+    });
+
+    Ok(best.unwrap())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cards::{parse_cards, Rank};
+
+    fn best(hole: &str, board: &str) -> OmahaHand {
+        best_hand(&parse_cards(hole).unwrap(), &parse_cards(board).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn one_hole_card_is_not_enough_for_a_flush() {
+        let hand = best("AhKsKdQc", "2h5h9hJh3c");
+        assert_eq!(hand.score, Score::Pair{card:Rank::King, kickers:[Rank::Five, Rank::Nine, Rank::Jack]});
+        assert_eq!(hand.hole_cards, ["Ks".parse::<Card>().unwrap(), "Kd".parse::<Card>().unwrap()]);
+    }
+
+    #[test]
+    fn board_straight_does_not_play() {
+        // The board is a straight but only three of its cards may be used.
+        let hand = best("2c2d7s7h", "9hTdJsQcKh");
+        assert_eq!(hand.score, Score::Pair{card:Rank::Seven, kickers:[Rank::Jack, Rank::Queen, Rank::King]});
+    }
+
+    #[test]
+    fn four_of_a_kind_needs_two_from_hand() {
+        // Three aces in hand and one on the board is only three of a kind.
+        let hand = best("AhAdAcKs", "As9c5d");
+        assert_eq!(hand.score, Score::ThreeOfAKind{card:Rank::Ace, high_kicker:Rank::Nine, low_kicker:Rank::Five});
+    }
+
+    #[test]
+    fn nut_flush_uses_two_hearts() {
+        let hand = best("AhKh2c3d", "QhJh4h8s9c");
+        assert_eq!(hand.score, Score::Flush{card:Rank::Ace, kickers:[Rank::Four, Rank::Jack, Rank::Queen, Rank::King]});
+        assert_eq!(hand.board_cards, ["Qh".parse::<Card>().unwrap(), "Jh".parse::<Card>().unwrap(), "4h".parse::<Card>().unwrap()]);
+    }
+
+    #[test]
+    fn five_card_omaha() {
+        let hand = best("TsJs2c3d4h", "QsKsAs7c8d");
+        assert_eq!(hand.score, Score::StraightFlush(Rank::Ace));
+    }
+
+    #[test]
+    fn wrong_card_counts() {
+        let board = parse_cards("QsKsAs").unwrap();
+        assert_eq!(best_hand(&parse_cards("TsJs2c").unwrap(), &board), Err(String::from("Required 4 to 6 hole cards but found 3.")));
+        assert_eq!(best_hand(&parse_cards("TsJs2c3c").unwrap(), &board[..2]), Err(String::from("Required 3 to 5 board cards but found 2.")));
+        assert_eq!(best_hand(&parse_cards("TsJs2cAs").unwrap(), &board), Err(String::from("Card 'As' appears more than once.")));
+    }
+}
@@ -0,0 +1,168 @@
+//! # pot
+//!
+//! `pot` splits the chips in the middle in to a main pot and side pots and awards each one to the best hands
+//! that are eligible for it.
+//!
+//! A player who is all in can only win as much from each opponent as they put in themselves.  Every all in
+//! amount starts a new side pot, and a player is eligible for every pot up to their own contribution as long as
+//! they haven't folded.  Folded players' chips stay in the pots they were put in to.
+
+use crate::rank_hands;
+
+/// A main or side pot and the seats that may win it.
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+pub struct Pot {
+    pub amount: u64,
+    /// The seats that may win this pot, in seat order.
+    pub eligible: Vec<usize>
+}
+
+/// Split the chips each seat put in to the hand in to the main pot followed by the side pots.
+///
+/// # Examples
+/// ```
+/// use poker::pot::{side_pots, Pot};
+///
+/// // Seat 0 is all in for 50 and seat 2 folded after putting in 20.
+/// let pots = side_pots(&[50, 100, 20, 100], &[false, false, true, false]);
+/// assert_eq!(pots, vec![
+///     Pot { amount: 170, eligible: vec![0, 1, 3] },
+///     Pot { amount: 100, eligible: vec![1, 3] },
+/// ]);
+/// ```
+pub fn side_pots(contributions: &[u64], folded: &[bool]) -> Vec<Pot> {
+    let mut levels: Vec<u64> = contributions.iter().zip(folded)
+        .filter(|(_, folded)| !**folded)
+        .map(|(contribution, _)| *contribution)
+        .collect();
+    levels.sort_unstable();
+    levels.dedup();
+
+    let mut pots: Vec<Pot> = Vec::new();
+    let mut previous = 0;
+    for level in levels {
+        let amount = contributions.iter().map(|contribution| (*contribution).min(level) - (*contribution).min(previous)).sum();
+        let eligible = (0..contributions.len()).filter(|seat| !folded[*seat] && contributions[*seat] >= level).collect();
+        if amount > 0 {
+            pots.push(Pot { amount, eligible });
+        }
+        previous = level;
+    }
+
+    // Chips a folded player put in above everyone still in the hand go to the last pot.
+    let leftover: u64 = contributions.iter().map(|contribution| contribution.saturating_sub(previous)).sum();
+    if let Some(last) = pots.last_mut() {
+        last.amount += leftover;
+    }
+
+    pots
+}
+
+/// Award each pot to the best of the eligible hands, returning the chips won by each seat.
+///
+/// `scores` holds each seat's hand, `None` for a hand that wasn't shown, which loses to any shown hand.  Tied
+/// hands split a pot and the odd chips go one each to the winners closest to the left of the button.  This works
+/// for anything ordered with the stronger hand `Greater`, like `rank_hands`.
+///
+/// # Examples
+/// ```
+/// use poker::pot::{award, side_pots};
+/// use poker::PokerHand;
+///
+/// let scores: Vec<_> = ["AAAA2", "QQQ23", "23457"].iter()
+///     .map(|hand| Some(PokerHand::from_str(hand).unwrap().score()))
+///     .collect();
+/// // The short stack has the best hand and wins the main pot, the trips win the side pot.
+/// let pots = side_pots(&[10, 50, 50], &[false; 3]);
+/// assert_eq!(award(&pots, &scores, 0), vec![30, 80, 0]);
+/// ```
+pub fn award<T: Ord>(pots: &[Pot], scores: &[Option<T>], button: usize) -> Vec<u64> {
+    let mut won = vec![0; scores.len()];
+    for pot in pots {
+        if pot.eligible.is_empty() {
+            continue;
+        }
+
+        let hands: Vec<&Option<T>> = pot.eligible.iter().map(|seat| &scores[*seat]).collect();
+        let mut winners: Vec<usize> = rank_hands(&hands)[0].iter().map(|idx| pot.eligible[*idx]).collect();
+        winners.sort_by_key(|seat| (seat + scores.len() - button - 1) % scores.len());
+
+        let count = winners.len() as u64;
+        for (place, seat) in winners.iter().enumerate() {
+            won[*seat] += pot.amount / count + if (place as u64) < pot.amount % count { 1 } else { 0 };
+        }
+    }
+
+    won
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{PokerHand, Score};
+
+    fn scores(hands: &[Option<&str>]) -> Vec<Option<Score>> {
+        hands.iter().map(|hand| hand.map(|hand| PokerHand::from_str(hand).unwrap().score())).collect()
+    }
+
+    #[test]
+    fn single_pot() {
+        let pots = side_pots(&[100, 100, 100], &[false, false, false]);
+        assert_eq!(pots, vec![Pot { amount: 300, eligible: vec![0, 1, 2] }]);
+    }
+
+    #[test]
+    fn several_all_ins() {
+        let pots = side_pots(&[25, 100, 60, 100, 60], &[false; 5]);
+        assert_eq!(pots, vec![
+            Pot { amount: 125, eligible: vec![0, 1, 2, 3, 4] },
+            Pot { amount: 140, eligible: vec![1, 2, 3, 4] },
+            Pot { amount: 80, eligible: vec![1, 3] },
+        ]);
+    }
+
+    #[test]
+    fn folded_chips_stay_in_their_pots() {
+        // Seat 1 put in more than the all in player before folding.
+        let pots = side_pots(&[30, 80, 100, 100], &[false, true, false, false]);
+        assert_eq!(pots, vec![
+            Pot { amount: 120, eligible: vec![0, 2, 3] },
+            Pot { amount: 190, eligible: vec![2, 3] },
+        ]);
+
+        // Only possible when an uncalled bet wasn't returned.
+        let pots = side_pots(&[50, 80], &[false, true]);
+        assert_eq!(pots, vec![Pot { amount: 130, eligible: vec![0] }]);
+        assert_eq!(side_pots(&[0, 0], &[false, false]), Vec::new());
+    }
+
+    #[test]
+    fn best_eligible_hand_wins_each_pot() {
+        let pots = side_pots(&[25, 100, 60, 100, 60], &[false; 5]);
+        let scores = scores(&[Some("AAAA2"), Some("22345"), Some("KKK23"), Some("QQ234"), Some("JJ234")]);
+        assert_eq!(award(&pots, &scores, 0), vec![125, 0, 140, 80, 0]);
+    }
+
+    #[test]
+    fn split_side_pot_with_odd_chip() {
+        // Seats 1 and 3 tie for the side pot of 81, seat 3 is closer to the left of the button.
+        let pots = side_pots(&[20, 60, 20, 60, 21], &[false, false, true, false, true]);
+        assert_eq!(pots[1], Pot { amount: 81, eligible: vec![1, 3] });
+        let scores = scores(&[Some("AAAA2"), Some("KKQQ2"), None, Some("KKQQ2"), None]);
+        assert_eq!(award(&pots, &scores, 2), vec![100, 40, 0, 41, 0]);
+        assert_eq!(award(&pots, &scores, 0), vec![100, 41, 0, 40, 0]);
+    }
+
+    #[test]
+    fn mucked_hand_loses() {
+        let pots = side_pots(&[50, 50], &[false, false]);
+        let scores = scores(&[None, Some("23457")]);
+        assert_eq!(award(&pots, &scores, 0), vec![0, 100]);
+    }
+
+    #[test]
+    fn last_player_standing() {
+        let pots = side_pots(&[10, 30, 5], &[true, false, true]);
+        assert_eq!(award(&pots, &vec![None::<Score>; 3], 0), vec![0, 45, 0]);
+    }
+}
@@ -0,0 +1,481 @@
+//! # range
+//!
+//! `range` parses the range notation players use to describe the hole cards an opponent might hold, such as
+//! "QQ+, AKs, A5s-A2s, 76o", in to the two card combinations it covers.
+//!
+//! * `QQ` is a pair, `AKs` is suited, `AKo` is offsuit and `AK` is both.
+//! * `QQ+` adds the bigger pairs and `K9s+` raises the kicker up to `KQs`.
+//! * `A5s-A2s` and `JJ-88` cover everything in between.
+//! * `AhKh` is one combination.
+//! * `AKs:0.5` gives a weight, the fraction of the time the hand is held.
+
+use crate::card_seq;
+use crate::cards::{Card, Rank, Suit};
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+
+/// Ranks from highest to lowest.
+const HIGH_TO_LOW: [Rank; 13] = [
+    Rank::Ace, Rank::King, Rank::Queen, Rank::Jack, Rank::Ten, Rank::Nine, Rank::Eight,
+    Rank::Seven, Rank::Six, Rank::Five, Rank::Four, Rank::Three, Rank::Two
+];
+
+/// Two hole cards, with the higher card first.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash, PartialOrd, Ord)]
+pub struct Combo {
+    high: Card,
+    low: Card
+}
+
+impl Combo {
+    /// The combination of two different cards, in either order.
+    pub fn new(first: Card, second: Card) -> Result<Combo, String> {
+        if first == second {
+            return Err(format!("Card '{}' appears more than once.", first));
+        }
+
+        if (card_seq(&first.rank), first.suit) > (card_seq(&second.rank), second.suit) {
+            Ok(Combo { high: first, low: second })
+        } else {
+            Ok(Combo { high: second, low: first })
+        }
+    }
+
+    pub fn cards(&self) -> [Card; 2] {
+        [self.high, self.low]
+    }
+
+    /// Whether the combination uses any of `cards`.
+    pub fn conflicts(&self, cards: &[Card]) -> bool {
+        cards.contains(&self.high) || cards.contains(&self.low)
+    }
+
+    /// The order combinations are written in, the highest first.
+    fn sort_key(&self) -> (usize, usize, Suit, Suit) {
+        (card_seq(&self.high.rank), card_seq(&self.low.rank), self.high.suit, self.low.suit)
+    }
+}
+
+impl fmt::Display for Combo {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}{}", self.high, self.low)
+    }
+}
+
+/// A set of two card combinations, each with a weight between 0 and 1.
+///
+/// # Examples
+/// ```
+/// use poker::range::Range;
+///
+/// let range: Range = "QQ+, AKs, A5s-A2s, 76o".parse().unwrap();
+/// assert_eq!(range.len(), 18 + 4 + 16 + 12);
+/// assert_eq!(range.to_string(), "QQ+, AKs, A5s-A2s, 76o");
+/// ```
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct Range {
+    combos: BTreeMap<Combo, f64>
+}
+
+impl Range {
+    pub fn new() -> Range {
+        Range { combos: BTreeMap::new() }
+    }
+
+    /// Add a combination, replacing its weight if it is already in the range.
+    pub fn insert(&mut self, combo: Combo, weight: f64) {
+        self.combos.insert(combo, weight);
+    }
+
+    /// The weight of a combination, or `None` if it is not in the range.
+    pub fn weight(&self, combo: &Combo) -> Option<f64> {
+        self.combos.get(combo).cloned()
+    }
+
+    /// Every combination and its weight.
+    pub fn combos(&self) -> impl Iterator<Item = (Combo, f64)> + '_ {
+        self.combos.iter().map(|(combo, weight)| (*combo, *weight))
+    }
+
+    pub fn len(&self) -> usize {
+        self.combos.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.combos.is_empty()
+    }
+}
+
+/// The shape of a hand before suits are chosen.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+enum Class {
+    Pair(Rank),
+    Suited(Rank, Rank),
+    Offsuit(Rank, Rank),
+    Any(Rank, Rank)
+}
+
+impl Class {
+    fn parse(input: &str) -> Result<Class, String> {
+        let chars: Vec<char> = input.chars().collect();
+        if chars.len() < 2 || chars.len() > 3 {
+            return Err(format!("Hand '{}' is not valid.", input));
+        }
+
+        let first = Rank::from_char(chars[0])?;
+        let second = Rank::from_char(chars[1])?;
+        let (high, low) = if card_seq(&first) >= card_seq(&second) { (first, second) } else { (second, first) };
+
+        match (high == low, chars.get(2)) {
+            (true, None) => Ok(Class::Pair(high)),
+            (false, None) => Ok(Class::Any(high, low)),
+            (false, Some('s')) => Ok(Class::Suited(high, low)),
+            (false, Some('o')) => Ok(Class::Offsuit(high, low)),
+            _ => Err(format!("Hand '{}' is not valid.", input))
+        }
+    }
+
+    fn combos(self) -> Vec<Combo> {
+        let mut combos = Vec::new();
+        for (i, first) in Suit::ALL.iter().enumerate() {
+            for (j, second) in Suit::ALL.iter().enumerate() {
+                let (high, low, wanted) = match self {
+                    Class::Pair(rank) => (rank, rank, i < j),
+                    Class::Suited(high, low) => (high, low, i == j),
+                    Class::Offsuit(high, low) => (high, low, i != j),
+                    Class::Any(high, low) => (high, low, true)
+                };
+
+                if wanted {
+                    combos.push(Combo::new(Card::new(high, *first), Card::new(low, *second)).unwrap());
+                }
+            }
+        }
+
+        combos
+    }
+
+    /// The same class with a different rank, the pair rank or the low card.
+    fn with_rank(self, rank: Rank) -> Class {
+        match self {
+            Class::Pair(_) => Class::Pair(rank),
+            Class::Suited(high, _) => Class::Suited(high, rank),
+            Class::Offsuit(high, _) => Class::Offsuit(high, rank),
+            Class::Any(high, _) => Class::Any(high, rank)
+        }
+    }
+
+    /// The rank that `+` and `-` move, the pair rank or the low card.
+    fn moving_rank(self) -> Rank {
+        match self {
+            Class::Pair(rank) => rank,
+            Class::Suited(_, low) | Class::Offsuit(_, low) | Class::Any(_, low) => low
+        }
+    }
+
+    /// Whether two classes only differ by their moving rank.
+    fn same_shape(self, other: Class) -> bool {
+        self.with_rank(Rank::Two) == other.with_rank(Rank::Two)
+    }
+
+    /// The highest the moving rank can go, aces for a pair or one below the high card.
+    fn top(self) -> Rank {
+        match self {
+            Class::Pair(_) => Rank::Ace,
+            Class::Suited(high, _) | Class::Offsuit(high, _) | Class::Any(high, _) => {
+                HIGH_TO_LOW[rank_idx(high) + 1]
+            }
+        }
+    }
+}
+
+/// The position of a rank in `HIGH_TO_LOW`.
+fn rank_idx(rank: Rank) -> usize {
+    14 - card_seq(&rank)
+}
+
+/// Every class with a moving rank from `high` down to `low`.
+fn class_range(class: Class, high: Rank, low: Rank) -> Vec<Combo> {
+    HIGH_TO_LOW[rank_idx(high)..=rank_idx(low)].iter()
+        .flat_map(|rank| class.with_rank(*rank).combos())
+        .collect()
+}
+
+fn parse_token(token: &str) -> Result<Vec<Combo>, String> {
+    if let Some(idx) = token.find('-') {
+        let first = Class::parse(&token[..idx])?;
+        let second = Class::parse(&token[idx + 1..])?;
+        if !first.same_shape(second) {
+            return Err(format!("Range '{}' should only change one rank.", token));
+        }
+
+        let (high, low) = if card_seq(&first.moving_rank()) >= card_seq(&second.moving_rank()) {
+            (first.moving_rank(), second.moving_rank())
+        } else {
+            (second.moving_rank(), first.moving_rank())
+        };
+        return Ok(class_range(first, high, low));
+    }
+
+    if let Some(base) = token.strip_suffix('+') {
+        let class = Class::parse(base)?;
+        return Ok(class_range(class, class.top(), class.moving_rank()));
+    }
+
+    let chars: Vec<char> = token.chars().collect();
+    if chars.len() == 4 {
+        let combo = Combo::new(Card::from_chars(chars[0], chars[1])?, Card::from_chars(chars[2], chars[3])?)?;
+        return Ok(vec![combo]);
+    }
+
+    Ok(Class::parse(token)?.combos())
+}
+
+impl FromStr for Range {
+    type Err = String;
+
+    fn from_str(input: &str) -> Result<Range, String> {
+        let mut range = Range::new();
+        for token in input.split(',').map(|token| token.trim()).filter(|token| !token.is_empty()) {
+            let (hands, weight) = match token.find(':') {
+                Some(idx) => {
+                    let weight = token[idx + 1..].trim().parse::<f64>()
+                        .map_err(|_| format!("Weight '{}' is not a number.", &token[idx + 1..]))?;
+                    (token[..idx].trim(), weight)
+                },
+                None => (token, 1.0)
+            };
+
+            if !(weight > 0.0 && weight <= 1.0) {
+                return Err(format!("Weight {} should be greater than 0 and at most 1.", weight));
+            }
+
+            for combo in parse_token(hands)? {
+                range.insert(combo, weight);
+            }
+        }
+
+        Ok(range)
+    }
+}
+
+/// A run of ranks written as one token, from `high` down to `low`.
+struct Run {
+    high: Rank,
+    low: Rank,
+    weight: f64
+}
+
+/// Split the ranks with a weight in to runs of neighbouring ranks that share a weight.
+fn runs(weights: &[Option<f64>; 13]) -> Vec<Run> {
+    let mut runs: Vec<Run> = Vec::new();
+    for (idx, weight) in weights.iter().enumerate() {
+        if let Some(weight) = weight {
+            match runs.last_mut() {
+                Some(run) if rank_idx(run.low) + 1 == idx && run.weight == *weight => run.low = HIGH_TO_LOW[idx],
+                _ => runs.push(Run { high: HIGH_TO_LOW[idx], low: HIGH_TO_LOW[idx], weight: *weight })
+            }
+        }
+    }
+
+    runs
+}
+
+/// Write a run of a class in the shortest notation.
+fn write_run(class: Class, run: &Run) -> String {
+    let name = |class: Class| match class {
+        Class::Pair(rank) => format!("{}{}", rank, rank),
+        Class::Suited(high, low) => format!("{}{}s", high, low),
+        Class::Offsuit(high, low) => format!("{}{}o", high, low),
+        Class::Any(high, low) => format!("{}{}", high, low)
+    };
+
+    let hands = if run.high == run.low {
+        name(class.with_rank(run.high))
+    } else if run.high == class.top() {
+        format!("{}+", name(class.with_rank(run.low)))
+    } else {
+        format!("{}-{}", name(class.with_rank(run.high)), name(class.with_rank(run.low)))
+    };
+
+    with_weight(hands, run.weight)
+}
+
+fn with_weight(hands: String, weight: f64) -> String {
+    if weight == 1.0 {
+        hands
+    } else {
+        format!("{}:{}", hands, weight)
+    }
+}
+
+/// Writes the range in short, canonical notation, so that parsing it again gives the same range.
+impl fmt::Display for Range {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        // Group the combinations by their class, only a whole class with one weight can be written by rank.
+        let mut classes: BTreeMap<(usize, usize, bool), Vec<(Combo, f64)>> = BTreeMap::new();
+        for (combo, weight) in self.combos() {
+            let suited = combo.high.suit == combo.low.suit;
+            let key = (rank_idx(combo.high.rank), rank_idx(combo.low.rank), suited);
+            classes.entry(key).or_default().push((combo, weight));
+        }
+
+        let mut pairs: [Option<f64>; 13] = [None; 13];
+        let mut suited: Vec<[Option<f64>; 13]> = vec![[None; 13]; 13];
+        let mut offsuit: Vec<[Option<f64>; 13]> = vec![[None; 13]; 13];
+        let mut singles: Vec<(Combo, f64)> = Vec::new();
+        for ((high, low, is_suited), combos) in classes {
+            let size = if high == low { 6 } else if is_suited { 4 } else { 12 };
+            let weight = combos[0].1;
+            if combos.len() != size || combos.iter().any(|(_, w)| *w != weight) {
+                singles.extend(combos);
+            } else if high == low {
+                pairs[high] = Some(weight);
+            } else if is_suited {
+                suited[high][low] = Some(weight);
+            } else {
+                offsuit[high][low] = Some(weight);
+            }
+        }
+
+        let mut tokens: Vec<String> = runs(&pairs).iter()
+            .map(|run| write_run(Class::Pair(Rank::Two), run))
+            .collect();
+
+        for (idx, high) in HIGH_TO_LOW.iter().enumerate() {
+            let suited_runs = runs(&suited[idx]);
+            let mut offsuit_runs = runs(&offsuit[idx]);
+
+            // Suited and offsuit runs that match are written once without the suffix.
+            let mut high_tokens: Vec<(usize, String)> = Vec::new();
+            for run in suited_runs {
+                let same = offsuit_runs.iter()
+                    .position(|other| other.high == run.high && other.low == run.low && other.weight == run.weight);
+                let class = match same {
+                    Some(position) => {
+                        offsuit_runs.remove(position);
+                        Class::Any(*high, run.low)
+                    },
+                    None => Class::Suited(*high, run.low)
+                };
+                high_tokens.push((rank_idx(run.high), write_run(class, &run)));
+            }
+
+            for run in offsuit_runs {
+                high_tokens.push((rank_idx(run.high), write_run(Class::Offsuit(*high, run.low), &run)));
+            }
+
+            // Keep the kickers in order, the sort is stable so suited stays ahead of offsuit.
+            high_tokens.sort_by_key(|(kicker, _)| *kicker);
+            tokens.extend(high_tokens.into_iter().map(|(_, token)| token));
+        }
+
+        singles.sort_by_key(|(combo, _)| std::cmp::Reverse(combo.sort_key()));
+        tokens.extend(singles.iter().map(|(combo, weight)| with_weight(combo.to_string(), *weight)));
+
+        write!(f, "{}", tokens.join(", "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn range(input: &str) -> Range {
+        input.parse().unwrap()
+    }
+
+    fn combo(input: &str) -> Combo {
+        let chars: Vec<char> = input.chars().collect();
+        Combo::new(Card::from_chars(chars[0], chars[1]).unwrap(), Card::from_chars(chars[2], chars[3]).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn class_sizes() {
+        assert_eq!(range("QQ").len(), 6);
+        assert_eq!(range("AKs").len(), 4);
+        assert_eq!(range("AKo").len(), 12);
+        assert_eq!(range("AK").len(), 16);
+        assert_eq!(range("KA").len(), 16);
+    }
+
+    #[test]
+    fn plus_notation() {
+        assert_eq!(range("QQ+"), range("QQ, KK, AA"));
+        assert_eq!(range("K9s+"), range("KQs, KJs, KTs, K9s"));
+        assert_eq!(range("AKo+"), range("AKo"));
+        assert_eq!(range("22+").len(), 78);
+    }
+
+    #[test]
+    fn dash_ranges() {
+        assert_eq!(range("A5s-A2s"), range("A5s, A4s, A3s, A2s"));
+        assert_eq!(range("A2s-A5s"), range("A5s-A2s"));
+        assert_eq!(range("JJ-99"), range("JJ, TT, 99"));
+        assert_eq!(range("KTo-K8o").len(), 36);
+    }
+
+    #[test]
+    fn specific_combos() {
+        let range = range("AhKh, 7c7d");
+        assert_eq!(range.len(), 2);
+        assert_eq!(range.weight(&combo("KhAh")), Some(1.0));
+        assert_eq!(range.weight(&combo("7d7c")), Some(1.0));
+        assert_eq!(range.weight(&combo("AsKs")), None);
+    }
+
+    #[test]
+    fn weights() {
+        let range = range("AKs:0.5, QQ+, AsKs");
+        assert_eq!(range.weight(&combo("AhKh")), Some(0.5));
+        assert_eq!(range.weight(&combo("AsKs")), Some(1.0));
+        assert_eq!(range.weight(&combo("AsAh")), Some(1.0));
+    }
+
+    #[test]
+    fn combo_conflicts() {
+        let ak = combo("AhKh");
+        assert_eq!(ak.cards(), [Card::new(Rank::Ace, Suit::Hearts), Card::new(Rank::King, Suit::Hearts)]);
+        assert!(ak.conflicts(&[Card::new(Rank::King, Suit::Hearts)]));
+        assert!(!ak.conflicts(&[Card::new(Rank::King, Suit::Spades)]));
+    }
+
+    #[test]
+    fn bad_ranges() {
+        assert_eq!("AKx".parse::<Range>(), Err(String::from("Hand 'AKx' is not valid.")));
+        assert_eq!("AAs".parse::<Range>(), Err(String::from("Hand 'AAs' is not valid.")));
+        assert_eq!("A5s-K2s".parse::<Range>(), Err(String::from("Range 'A5s-K2s' should only change one rank.")));
+        assert_eq!("A5s-A2o".parse::<Range>(), Err(String::from("Range 'A5s-A2o' should only change one rank.")));
+        assert_eq!("AhAh".parse::<Range>(), Err(String::from("Card 'Ah' appears more than once.")));
+        assert_eq!("AKs:x".parse::<Range>(), Err(String::from("Weight 'x' is not a number.")));
+        assert_eq!("AKs:1.5".parse::<Range>(), Err(String::from("Weight 1.5 should be greater than 0 and at most 1.")));
+        assert_eq!("1K".parse::<Range>(), Err(String::from("Character '1' is not valid.")));
+    }
+
+    #[test]
+    fn canonical_strings() {
+        assert_eq!(range("AA, KK, QQ").to_string(), "QQ+");
+        assert_eq!(range("AKs, AKo").to_string(), "AK");
+        assert_eq!(range("A2s, A3s, A4s, A5s").to_string(), "A5s-A2s");
+        assert_eq!(range("KQs, KJs, KTs").to_string(), "KTs+");
+        assert_eq!(range("TT, 99, 88, 22").to_string(), "TT-88, 22");
+        assert_eq!(range("AKs, AQs, AKo").to_string(), "AQs+, AKo");
+        assert_eq!(range("AKs, AQs, AKo, AQo").to_string(), "AQ+");
+        assert_eq!(range("AKs, AQs, AKo, AQo, AJs").to_string(), "AJs+, AQo+");
+        assert_eq!(range("AhKh, AsKs").to_string(), "AsKs, AhKh");
+        assert_eq!(range("QQ+:0.5, AKs").to_string(), "QQ+:0.5, AKs");
+        assert_eq!(range("KK+, QQ:0.5").to_string(), "KK+, QQ:0.5");
+        assert_eq!(range("").to_string(), "");
+    }
+
+    #[test]
+    fn round_trip() {
+        for input in ["QQ+, AKs, A5s-A2s, 76o", "22+, A2+, K9s+, QTo-Q8o", "AKs:0.5, AhKh, JJ-99:0.25, 72o, 7h2h",
+                "AsKs, AsKh, AsKd, AsKc", "K2s-K5s, K7s, KQs:0.3, KJs, KTo+"].iter() {
+            let parsed = range(input);
+            assert_eq!(range(&parsed.to_string()), parsed, "{} was written as {}", input, parsed);
+        }
+    }
+}
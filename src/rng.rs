@@ -0,0 +1,81 @@
+//! # rng
+//!
+//! `rng` is the random number source used for shuffling.  The crate has no dependencies so `Rng` is a small
+//! trait that can be implemented for any generator, and `SplitMix64` is a seedable default so that a deal can
+//! be repeated from its seed.
+
+/// A source of random numbers.
+pub trait Rng {
+    /// The next 64 random bits.
+    fn next_u64(&mut self) -> u64;
+
+    /// A random number from `0` up to but not including `bound`, without modulo bias.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `bound` is zero.
+    fn below(&mut self, bound: usize) -> usize {
+        assert!(bound > 0, "The bound must be greater than zero.");
+
+        let bound = bound as u64;
+        let zone = u64::MAX - (u64::MAX - bound + 1) % bound;
+        loop {
+            let value = self.next_u64();
+            if value <= zone {
+                return (value % bound) as usize;
+            }
+        }
+    }
+}
+
+/// Sebastiano Vigna's SplitMix64 generator, small and fast and good enough for dealing cards.
+///
+/// # Examples
+/// ```
+/// use poker::rng::{Rng, SplitMix64};
+///
+/// let mut first = SplitMix64::new(42);
+/// let mut second = SplitMix64::new(42);
+/// assert_eq!(first.next_u64(), second.next_u64());
+/// ```
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct SplitMix64 {
+    state: u64
+}
+
+impl SplitMix64 {
+    pub fn new(seed: u64) -> SplitMix64 {
+        SplitMix64 { state: seed }
+    }
+}
+
+impl Rng for SplitMix64 {
+    fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn split_mix_reference_values() {
+        let mut rng = SplitMix64::new(0);
+        assert_eq!(rng.next_u64(), 0xE220_A839_7B1D_CDAF);
+        assert_eq!(rng.next_u64(), 0x6E78_9E6A_A1B9_65F4);
+    }
+
+    #[test]
+    fn below_stays_in_bounds() {
+        let mut rng = SplitMix64::new(7);
+        for bound in 1..100 {
+            assert!(rng.below(bound) < bound);
+        }
+        assert_eq!(rng.below(1), 0);
+    }
+}
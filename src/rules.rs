@@ -0,0 +1,276 @@
+//! # rules
+//!
+//! `rules` describes the parts of hand ranking that change between games, such as short deck Hold'em where
+//! the deck starts at the six, A-6-7-8-9 is a straight and a flush beats a full house, or lowball where the
+//! worst hand wins.
+//!
+//! There are presets for the games we support, and any other game can be described by setting the fields,
+//! starting from a preset:
+//!
+//! ```
+//! use poker::rules::RuleSet;
+//!
+//! // High hands where kickers never break a tie.
+//! let no_kickers = RuleSet { kickers: false, ..RuleSet::standard() };
+//! assert_ne!(no_kickers, RuleSet::standard());
+//! ```
+
+use crate::cards::{Card, Deck, Rank};
+use crate::{card_seq, Category};
+
+use std::cmp::Ordering;
+
+/// The rules used to score and compare high hands.
+///
+/// # Examples
+/// ```
+/// use poker::rules::RuleSet;
+/// use poker::{compare_hands_with, PokerHand};
+/// use std::cmp::Ordering;
+///
+/// let flush = PokerHand::from_str("AhJh9h7h6h").unwrap().score();
+/// let full_house = PokerHand::from_str("KsKhKd6c6d").unwrap().score();
+/// assert_eq!(compare_hands_with(&flush, &full_house, &RuleSet::standard()), Ordering::Greater);
+/// assert_eq!(compare_hands_with(&flush, &full_house, &RuleSet::short_deck(false)), Ordering::Less);
+/// ```
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub struct RuleSet {
+    /// The lowest rank in the deck, a two for a standard deck and a six for short deck.
+    pub low_rank: Rank,
+    /// Whether the ace ranks above the king, otherwise it ranks below the two.
+    pub ace_high: bool,
+    /// Whether a high ace also plays low in a straight with the four lowest ranks, such as A-2-3-4-5.
+    pub ace_low_straight: bool,
+    /// Whether straights and flushes count, otherwise they are high card hands.
+    pub straights_and_flushes: bool,
+    /// Whether a flush ranks above a full house, as in short deck.
+    pub flush_beats_full_house: bool,
+    /// Whether three of a kind ranks above a straight, as in some short deck games.
+    pub trips_beat_straight: bool,
+    /// Whether kickers break ties, otherwise hands that differ only by their kickers split the pot.
+    pub kickers: bool,
+    /// Whether the worst high hand wins.
+    pub lowball: bool
+}
+
+impl RuleSet {
+    /// The usual rules for a 52 card deck.
+    pub fn standard() -> RuleSet {
+        RuleSet {
+            low_rank: Rank::Two,
+            ace_high: true,
+            ace_low_straight: true,
+            straights_and_flushes: true,
+            flush_beats_full_house: false,
+            trips_beat_straight: false,
+            kickers: true,
+            lowball: false
+        }
+    }
+
+    /// Short deck, or six plus, Hold'em with 36 cards.  Some games also rank three of a kind above a straight.
+    pub fn short_deck(trips_beat_straight: bool) -> RuleSet {
+        RuleSet {
+            low_rank: Rank::Six,
+            flush_beats_full_house: true,
+            trips_beat_straight,
+            ..RuleSet::standard()
+        }
+    }
+
+    /// Ace-to-five lowball, where the ace is low, straights and flushes don't count and A-2-3-4-5 is the best
+    /// hand.  This ranks hands the same as `lowball::ace_to_five`.
+    pub fn ace_to_five() -> RuleSet {
+        RuleSet {
+            ace_high: false,
+            straights_and_flushes: false,
+            lowball: true,
+            ..RuleSet::standard()
+        }
+    }
+
+    /// Deuce-to-seven lowball, where the ace is high, straights and flushes count and 7-5-4-3-2 is the best
+    /// hand.  This ranks hands the same as `lowball::deuce_to_seven`.
+    pub fn deuce_to_seven() -> RuleSet {
+        RuleSet {
+            ace_low_straight: false,
+            lowball: true,
+            ..RuleSet::standard()
+        }
+    }
+
+    /// The sequence of a rank, from 1 for a low ace to 14 for a high ace.
+    pub fn seq(&self, rank: &Rank) -> usize {
+        match (rank, self.ace_high) {
+            (Rank::Ace, false) => 1,
+            _ => card_seq(rank)
+        }
+    }
+
+    /// The categories from weakest to strongest.
+    pub fn categories(&self) -> Vec<Category> {
+        let mut categories = vec![
+            Category::HighCard,
+            Category::Pair,
+            Category::TwoPair,
+            Category::ThreeOfAKind,
+            Category::Straight,
+            Category::Flush,
+            Category::FullHouse,
+            Category::FourOfAKind,
+            Category::StraightFlush,
+            Category::FiveOfAKind
+        ];
+        categories.sort_by_key(|category| self.strength(*category));
+        categories
+    }
+
+    /// Compare two categories, the stronger category is `Greater`.
+    pub fn cmp_categories(&self, left: Category, right: Category) -> Ordering {
+        self.strength(left).cmp(&self.strength(right))
+    }
+
+    /// The position of a category from weakest to strongest under these rules.
+    fn strength(&self, category: Category) -> u8 {
+        match category {
+            Category::ThreeOfAKind if self.trips_beat_straight => Category::Straight as u8,
+            Category::Straight if self.trips_beat_straight => Category::ThreeOfAKind as u8,
+            Category::Flush if self.flush_beats_full_house => Category::FullHouse as u8,
+            Category::FullHouse if self.flush_beats_full_house => Category::Flush as u8,
+            other => other as u8
+        }
+    }
+
+    /// True if the card is in the deck for this game.
+    pub fn in_deck(&self, card: &Card) -> bool {
+        card_seq(&card.rank) >= card_seq(&self.low_rank)
+    }
+
+    /// A new deck for this game, in order.
+    pub fn deck(&self) -> Deck {
+        Deck::from_cards(Card::all().into_iter().filter(|card| self.in_deck(card)).collect())
+    }
+}
+
+impl Default for RuleSet {
+    fn default() -> RuleSet {
+        RuleSet::standard()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cards::parse_cards;
+    use crate::lowball::{ace_to_five, deuce_to_seven};
+    use crate::{compare_hands_with, PokerHand, Score};
+
+    fn score(hand: &str, rules: &RuleSet) -> Score {
+        PokerHand::from_str(hand).unwrap().score_with(rules)
+    }
+
+    #[test]
+    fn short_deck_has_36_cards() {
+        let deck = RuleSet::short_deck(false).deck();
+        assert_eq!(deck.len(), 36);
+        assert!(deck.remaining().iter().all(|card| card_seq(&card.rank) >= 6));
+        assert_eq!(RuleSet::standard().deck().len(), 52);
+    }
+
+    #[test]
+    fn short_deck_wheel() {
+        let rules = RuleSet::short_deck(false);
+        assert_eq!(score("A6789", &rules), Score::Straight(Rank::Nine));
+        assert_eq!(score("Ah6h7h8h9h", &rules), Score::StraightFlush(Rank::Nine));
+        assert_eq!(score("A6789", &RuleSet::standard()).category(), Category::HighCard);
+        assert_eq!(score("A2345", &rules).category(), Category::HighCard);
+        assert_eq!(compare_hands_with(&score("A6789", &rules), &score("6789T", &rules), &rules), Ordering::Greater);
+    }
+
+    #[test]
+    fn category_order() {
+        let rules = RuleSet::short_deck(true);
+        assert_eq!(rules.cmp_categories(Category::ThreeOfAKind, Category::Straight), Ordering::Greater);
+        assert_eq!(rules.cmp_categories(Category::Flush, Category::FullHouse), Ordering::Greater);
+        assert_eq!(rules.cmp_categories(Category::Straight, Category::TwoPair), Ordering::Greater);
+
+        let rules = RuleSet::short_deck(false);
+        assert_eq!(rules.cmp_categories(Category::ThreeOfAKind, Category::Straight), Ordering::Less);
+        assert_eq!(rules.categories()[5..7], [Category::FullHouse, Category::Flush]);
+        assert_eq!(RuleSet::standard().categories()[3..7], [Category::ThreeOfAKind, Category::Straight, Category::Flush, Category::FullHouse]);
+        assert_eq!(RuleSet::short_deck(true).categories()[3..5], [Category::Straight, Category::ThreeOfAKind]);
+        assert_eq!(RuleSet::default(), RuleSet::standard());
+    }
+
+    #[test]
+    fn trips_beat_straight() {
+        let trips = score("777AK", &RuleSet::standard());
+        let straight = score("6789T", &RuleSet::standard());
+        // Like compare_hands, Less means the left hand wins.
+        assert_eq!(compare_hands_with(&trips, &straight, &RuleSet::standard()), Ordering::Greater);
+        assert_eq!(compare_hands_with(&trips, &straight, &RuleSet::short_deck(false)), Ordering::Greater);
+        assert_eq!(compare_hands_with(&trips, &straight, &RuleSet::short_deck(true)), Ordering::Less);
+    }
+
+    #[test]
+    fn short_deck_best_of() {
+        let rules = RuleSet::short_deck(false);
+        let best = PokerHand::best_of_with(&parse_cards("AsKd TcJh 7s8h9d").unwrap(), &rules).unwrap();
+        assert_eq!(best.score, Score::Straight(Rank::Jack));
+        let best = PokerHand::best_of_with(&parse_cards("AsKd 6c7h 8s9h Qd").unwrap(), &rules).unwrap();
+        assert_eq!(best.score, Score::Straight(Rank::Nine));
+        let best = PokerHand::best_of(&parse_cards("AsKd 6c7h 8s9h Qd").unwrap()).unwrap();
+        assert_eq!(best.score.category(), Category::HighCard);
+
+        assert_eq!(PokerHand::best_of_with(&parse_cards("AhKh JhJsJd 9h2h").unwrap(), &rules), Err(String::from("Card '2h' is not in the deck.")));
+    }
+
+    #[test]
+    fn ace_to_five_agrees_with_lowball() {
+        let rules = RuleSet::ace_to_five();
+        assert_eq!(score("A2345", &rules), Score::HighCard{ card: Rank::Five, kickers: [Rank::Ace, Rank::Two, Rank::Three, Rank::Four] });
+        assert_eq!(score("Ah2h3h4h5h", &rules).category(), Category::HighCard);
+        assert_eq!(score("TJQKA", &rules).category(), Category::HighCard);
+
+        // Every pair of hands compares the same as `LowScore`, where the better low is `Greater`.
+        let hands = ["A2345", "A2346", "8432A", "87654", "KQJT9", "AA234", "22A34", "AA22K", "AAA23", "AAA22", "AAAA2", "65432"];
+        for left in hands.iter() {
+            for right in hands.iter() {
+                let expected = ace_to_five(PokerHand::from_str(right).unwrap().ranks())
+                    .cmp(&ace_to_five(PokerHand::from_str(left).unwrap().ranks()));
+                assert_eq!(compare_hands_with(&score(left, &rules), &score(right, &rules), &rules), expected, "{} {}", left, right);
+            }
+        }
+    }
+
+    #[test]
+    fn deuce_to_seven_agrees_with_lowball() {
+        let rules = RuleSet::deuce_to_seven();
+        let hands = ["7c5d4h3s2c", "7c6d4h3s2c", "Ac5d4h3s2c", "Kc5d4h3s2c", "6c5d4h3s2c", "7c5c4c3c2c", "AcAd4h3s5c", "KcKdKhQsQc"];
+        for left in hands.iter() {
+            for right in hands.iter() {
+                let expected = deuce_to_seven(&PokerHand::from_str(right).unwrap())
+                    .cmp(&deuce_to_seven(&PokerHand::from_str(left).unwrap()));
+                assert_eq!(compare_hands_with(&score(left, &rules), &score(right, &rules), &rules), expected, "{} {}", left, right);
+            }
+        }
+    }
+
+    #[test]
+    fn kickers_can_be_ignored() {
+        let rules = RuleSet { kickers: false, ..RuleSet::standard() };
+        assert_eq!(compare_hands_with(&score("AAKQJ", &rules), &score("AA234", &rules), &rules), Ordering::Equal);
+        assert_eq!(compare_hands_with(&score("AAKQJ", &rules), &score("KKQJT", &rules), &rules), Ordering::Less);
+        assert_eq!(compare_hands_with(&score("AAKKQ", &rules), &score("AAKK2", &rules), &rules), Ordering::Equal);
+        assert_eq!(compare_hands_with(&score("AAKQJ", &rules), &score("AA234", &RuleSet::standard()), &RuleSet::standard()), Ordering::Less);
+    }
+
+    #[test]
+    fn ace_always_low() {
+        // A user defined game where the ace is always low but straights and flushes still count.
+        let rules = RuleSet { ace_high: false, ..RuleSet::standard() };
+        assert_eq!(score("A2345", &rules), Score::Straight(Rank::Five));
+        assert_eq!(score("TJQKA", &rules).category(), Category::HighCard);
+        assert_eq!(compare_hands_with(&score("KK234", &rules), &score("AAKQJ", &rules), &rules), Ordering::Less);
+    }
+}
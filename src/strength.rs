@@ -0,0 +1,180 @@
+//! # strength
+//!
+//! `strength` maps scores to the standard equivalence class number of a five card hand, from 1 for a
+//! royal flush to 7462 for seven high.  Two hands compare with a single integer comparison and the number
+//! is small enough to store anywhere.
+
+use crate::cards::{Card, Suit};
+use crate::enumerate::rank_multisets;
+use crate::{PokerHand, Score};
+
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+/// The equivalence class of a five card hand, where 1 is the strongest and 7462 the weakest.
+///
+/// `HandRank` is ordered like `Score`, the stronger hand is `Greater` even though its number is smaller.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub struct HandRank(u16);
+
+impl HandRank {
+    /// The strongest hand, a royal flush.
+    pub const BEST: HandRank = HandRank(1);
+
+    /// The weakest hand, 7-5-4-3-2 without a flush.
+    pub const WORST: HandRank = HandRank(7462);
+
+    /// The rank with the given equivalence class number, if it is between 1 and 7462.
+    pub fn new(value: u16) -> Option<HandRank> {
+        if (HandRank::BEST.0..=HandRank::WORST.0).contains(&value) {
+            Some(HandRank(value))
+        } else {
+            None
+        }
+    }
+
+    /// The equivalence class number.
+    pub fn value(self) -> u16 {
+        self.0
+    }
+
+    /// The score of every hand in this equivalence class.
+    ///
+    /// # Examples
+    /// ```
+    /// use poker::Score;
+    /// use poker::cards::Rank;
+    /// use poker::strength::HandRank;
+    ///
+    /// assert_eq!(HandRank::BEST.score(), Score::StraightFlush(Rank::Ace));
+    /// assert_eq!(HandRank::new(11).unwrap().score().strength(), HandRank::new(11));
+    /// ```
+    pub fn score(self) -> Score {
+        classes()[usize::from(self.0) - 1].clone()
+    }
+}
+
+impl Ord for HandRank {
+    fn cmp(&self, other: &HandRank) -> Ordering {
+        other.0.cmp(&self.0)
+    }
+}
+
+impl PartialOrd for HandRank {
+    fn partial_cmp(&self, other: &HandRank) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Score {
+    /// The equivalence class of the score, or `None` for five of a kind which can't be dealt from one deck.
+    ///
+    /// # Examples
+    /// ```
+    /// use poker::PokerHand;
+    ///
+    /// let straight = PokerHand::from_str("23456").unwrap().score();
+    /// assert_eq!(straight.strength().unwrap().value(), 1608);
+    /// ```
+    pub fn strength(&self) -> Option<HandRank> {
+        index().get(self).map(|idx| HandRank(*idx))
+    }
+}
+
+/// Every equivalence class, from the strongest to the weakest.
+fn classes() -> &'static Vec<Score> {
+    static CLASSES: OnceLock<Vec<Score>> = OnceLock::new();
+    CLASSES.get_or_init(|| {
+        let mut classes: Vec<Score> = Vec::with_capacity(7462);
+        // Every multiset of ranks covers the hands without a flush.
+        for hand in rank_multisets() {
+            if hand[0] == hand[4] {
+                // Five of a kind has no equivalence class.
+                continue;
+            }
+
+            classes.push(PokerHand::from_ranks(hand).score());
+
+            // Five different ranks can also be a flush.
+            if hand.windows(2).all(|pair| pair[0] != pair[1]) {
+                classes.push(PokerHand::new(hand.map(|rank| Card::new(rank, Suit::Spades))).score());
+            }
+        }
+
+        classes.sort_by(|left, right| right.cmp(left));
+        classes
+    })
+}
+
+fn index() -> &'static HashMap<Score, u16> {
+    static INDEX: OnceLock<HashMap<Score, u16>> = OnceLock::new();
+    INDEX.get_or_init(|| {
+        classes().iter()
+            .enumerate()
+            .map(|(idx, score)| (score.clone(), idx as u16 + 1))
+            .collect()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strength(hand: &str) -> u16 {
+        PokerHand::from_str(hand).unwrap().score().strength().unwrap().value()
+    }
+
+    #[test]
+    fn there_are_7462_classes() {
+        assert_eq!(classes().len(), 7462);
+        assert_eq!(index().len(), 7462);
+    }
+
+    #[test]
+    fn category_boundaries() {
+        assert_eq!(strength("AsKsQsJsTs"), 1);
+        assert_eq!(strength("5s4s3s2sAs"), 10);
+        assert_eq!(strength("AAAAK"), 11);
+        assert_eq!(strength("22223"), 166);
+        assert_eq!(strength("AAAKK"), 167);
+        assert_eq!(strength("22233"), 322);
+        assert_eq!(strength("AsKsQsJs9s"), 323);
+        assert_eq!(strength("7s5s4s3s2s"), 1599);
+        assert_eq!(strength("AKQJT"), 1600);
+        assert_eq!(strength("5432A"), 1609);
+        assert_eq!(strength("AAAKQ"), 1610);
+        assert_eq!(strength("22243"), 2467);
+        assert_eq!(strength("AAKKQ"), 2468);
+        assert_eq!(strength("33224"), 3325);
+        assert_eq!(strength("AAKQJ"), 3326);
+        assert_eq!(strength("22543"), 6185);
+        assert_eq!(strength("AKQJ9"), 6186);
+        assert_eq!(strength("75432"), 7462);
+    }
+
+    #[test]
+    fn round_trip() {
+        for value in 1..=7462 {
+            let rank = HandRank::new(value).unwrap();
+            assert_eq!(rank.score().strength(), Some(rank));
+        }
+    }
+
+    #[test]
+    fn ordering_agrees_with_score() {
+        for value in 1..7462 {
+            let stronger = HandRank::new(value).unwrap();
+            let weaker = HandRank::new(value + 1).unwrap();
+            assert!(stronger > weaker);
+            assert!(stronger.score() > weaker.score());
+        }
+    }
+
+    #[test]
+    fn out_of_range() {
+        assert_eq!(HandRank::new(0), None);
+        assert_eq!(HandRank::new(7463), None);
+        assert_eq!(PokerHand::from_str("KKKKK").unwrap().score().strength(), None);
+    }
+}
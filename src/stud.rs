@@ -0,0 +1,548 @@
+//! # stud
+//!
+//! `stud` plays a single hand of seven-card stud one action at a time, from the antes to the showdown.  Like
+//! `holdem` there is no I/O and no randomness, the deck is shuffled by the caller.
+//!
+//! Each player gets two cards down and one up on third street, one up card on each of fourth, fifth and sixth
+//! street and a last card down on seventh street.  The lowest up card brings in the betting on third street,
+//! and on later streets the best hand showing acts first.  The betting is fixed limit, the small bet on third
+//! and fourth street and the big bet after that, and the best five of each player's seven cards wins.
+//!
+//! ```
+//! use poker::cards::Deck;
+//! use poker::rng::SplitMix64;
+//! use poker::stud::{Action, Hand, Legal, Stakes};
+//!
+//! let mut deck = Deck::new();
+//! deck.shuffle(&mut SplitMix64::new(7));
+//!
+//! let stakes = Stakes { ante: 1, bring_in: 2, small_bet: 5, big_bet: 10 };
+//! let mut hand = Hand::new(&[100, 100, 100], 0, stakes, deck).unwrap();
+//! while !hand.is_over() {
+//!     let action = if hand.legal_actions().contains(&Legal::Check) { Action::Check } else { Action::Call };
+//!     hand.apply(action).unwrap();
+//! }
+//! assert!(hand.players().iter().all(|player| player.cards.all().len() == 7));
+//! assert_eq!(hand.winnings().unwrap().iter().sum::<u64>(), 9);
+//! ```
+//!
+//! No cards are burned, so seven players can always be dealt seven cards each from one deck.
+
+pub use crate::betting::{Action, Legal};
+
+use crate::betting::Limit;
+use crate::cards::{Card, Deck, Rank};
+use crate::table::{self, Next, Table};
+use crate::{card_seq, group_ranks, Category, PokerHand, Score};
+
+use std::cmp::Ordering;
+
+/// The most players a 52 card deck can deal seven cards to.
+pub const MAX_PLAYERS: usize = 7;
+
+/// The bets in fixed limit stud, with the bring-in no bigger than the small bet.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub struct Stakes {
+    /// Paid by every player before the cards are dealt, or zero for no ante.
+    pub ante: u64,
+    /// Paid by the lowest up card on third street.
+    pub bring_in: u64,
+    /// The bet on third and fourth street.
+    pub small_bet: u64,
+    /// The bet on fifth, sixth and seventh street.
+    pub big_bet: u64
+}
+
+/// The betting rounds of a hand, named after how many cards each player has.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash, PartialOrd, Ord)]
+pub enum Street {
+    Third,
+    Fourth,
+    Fifth,
+    Sixth,
+    Seventh
+}
+
+/// The part of a hand made by the up cards, which only counts pairs, trips and quads because there are too
+/// few cards for a straight or a flush.  The better hand showing is `Greater`.
+///
+/// # Examples
+/// ```
+/// use poker::cards::parse_cards;
+/// use poker::stud::showing;
+///
+/// let pair = showing(&parse_cards("7s7h2c").unwrap());
+/// let ace_king = showing(&parse_cards("AsKhQc").unwrap());
+/// assert!(pair > ace_king);
+/// ```
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+pub struct Showing {
+    category: Category,
+    // Grouped by how many of each rank there are, then from highest to lowest.
+    ranks: Vec<Rank>
+}
+
+impl Showing {
+    /// One of `HighCard`, `Pair`, `TwoPair`, `ThreeOfAKind` or `FourOfAKind`.
+    pub fn category(&self) -> Category {
+        self.category
+    }
+
+    /// The ranks from the most to the least important.
+    pub fn ranks(&self) -> &[Rank] {
+        &self.ranks
+    }
+}
+
+impl Ord for Showing {
+    fn cmp(&self, other: &Showing) -> Ordering {
+        match self.category.cmp(&other.category) {
+            Ordering::Equal => self.ranks.iter().map(card_seq).cmp(other.ranks.iter().map(card_seq)),
+            less_or_greater => less_or_greater
+        }
+    }
+}
+
+impl PartialOrd for Showing {
+    fn partial_cmp(&self, other: &Showing) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Score up to four up cards.  Suits don't matter.
+pub fn showing(cards: &[Card]) -> Showing {
+    let ranks: Vec<Rank> = cards.iter().map(|card| card.rank).collect();
+    let groups = group_ranks(&ranks, card_seq);
+    let category = match (groups.first().map_or(0, |group| group.0), groups.get(1).map_or(0, |group| group.0)) {
+        (4, _) => Category::FourOfAKind,
+        (3, _) => Category::ThreeOfAKind,
+        (2, 2) => Category::TwoPair,
+        (2, _) => Category::Pair,
+        _ => Category::HighCard
+    };
+
+    let ranks = groups.iter().flat_map(|(count, rank)| vec![*rank; *count]).collect();
+    Showing { category, ranks }
+}
+
+/// A player's cards in stud.
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub struct Cards {
+    /// The cards only the player can see, the first two and the seventh.
+    pub down: Vec<Card>,
+    /// The cards everyone can see, in the order they were dealt.
+    pub up: Vec<Card>
+}
+
+impl Cards {
+    /// Every card the player has, down cards first.
+    pub fn all(&self) -> Vec<Card> {
+        let mut cards = self.down.clone();
+        cards.extend_from_slice(&self.up);
+        cards
+    }
+}
+
+/// A player in the hand.
+pub type Player = table::Player<Cards>;
+
+/// A hand of seven-card stud in progress.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Hand {
+    stakes: Stakes,
+    table: Table<Cards>,
+    dealer: usize,
+    bring_in: usize,
+    deck: Deck,
+    street: Street
+}
+
+impl Hand {
+    /// Seat the players, post the antes, deal third street starting to the left of the dealer and post the
+    /// bring-in.  The lowest up card brings in, with the ace high and ties broken by suit, clubs lowest then
+    /// diamonds, hearts and spades.  A player left with no chips after the ante doesn't bring in.  The player to
+    /// the left of the bring-in acts first and may call it or complete it to the small bet.
+    pub fn new(stacks: &[u64], dealer: usize, stakes: Stakes, mut deck: Deck) -> Result<Hand, String> {
+        let count = stacks.len();
+        if !(2..=MAX_PLAYERS).contains(&count) {
+            return Err(format!("Required 2 to {} players but found {}.", MAX_PLAYERS, count));
+        }
+
+        if dealer >= count {
+            return Err(format!("Seat {} is not at the table.", dealer));
+        }
+
+        if let Some(seat) = stacks.iter().position(|stack| *stack == 0) {
+            return Err(format!("Seat {} has no chips.", seat));
+        }
+
+        if stakes.bring_in == 0 || stakes.bring_in > stakes.small_bet || stakes.small_bet > stakes.big_bet {
+            return Err(String::from("The bring-in must be at least one chip and no bigger than the small bet, which is no bigger than the big bet."));
+        }
+
+        let needed = 7 * count;
+        if deck.len() < needed {
+            return Err(format!("Required {} cards but only {} remain.", needed, deck.len()));
+        }
+
+        let mut players: Vec<Player> = stacks.iter().map(|stack| Player::new(*stack, Cards::default())).collect();
+        for _ in 0..2 {
+            for offset in 1..=count {
+                players[(dealer + offset) % count].cards.down.push(deck.deal().unwrap());
+            }
+        }
+        for offset in 1..=count {
+            players[(dealer + offset) % count].cards.up.push(deck.deal().unwrap());
+        }
+
+        let mut table = Table::new(players, stakes.ante, Limit::FixedLimit{ max_bets: 4 }, stakes.small_bet);
+
+        // A player who is all in from the ante can't bring in, unless everyone is.
+        let players = table.players();
+        let up_card = |seat: &usize| {
+            let card = players[*seat].cards.up[0];
+            (card_seq(&card.rank), card.suit)
+        };
+        let bring_in = (0..count).filter(|seat| players[*seat].stack > 0).min_by_key(up_card)
+            .or_else(|| (0..count).min_by_key(up_card))
+            .unwrap();
+
+        table.round.post_bring_in(bring_in, stakes.bring_in);
+        table.round.start(bring_in);
+        table.sync();
+        let mut hand = Hand {
+            stakes,
+            table,
+            dealer,
+            bring_in,
+            deck,
+            street: Street::Third
+        };
+
+        hand.advance();
+        Ok(hand)
+    }
+
+    pub fn players(&self) -> &[Player] {
+        self.table.players()
+    }
+
+    pub fn dealer(&self) -> usize {
+        self.dealer
+    }
+
+    /// The seat that paid the bring-in on third street.
+    pub fn bring_in(&self) -> usize {
+        self.bring_in
+    }
+
+    pub fn street(&self) -> Street {
+        self.street
+    }
+
+    /// The seat of the player to act, or `None` once the hand is over.
+    pub fn to_act(&self) -> Option<usize> {
+        self.table.round.to_act()
+    }
+
+    /// The total bet on this street that a player must match to stay in the hand.
+    pub fn current_bet(&self) -> u64 {
+        self.table.round.current_bet()
+    }
+
+    /// All the chips in the middle, including bets on this street.
+    pub fn pot(&self) -> u64 {
+        self.table.pot()
+    }
+
+    pub fn is_over(&self) -> bool {
+        self.table.is_over()
+    }
+
+    /// The chips each seat won from the pot, once the hand is over.
+    pub fn winnings(&self) -> Option<&[u64]> {
+        self.table.winnings()
+    }
+
+    /// The actions the player to act may take, empty once the hand is over.
+    pub fn legal_actions(&self) -> Vec<Legal> {
+        self.table.legal_actions()
+    }
+
+    /// Take an action for the player to act and move the hand on, dealing the next street or going to the
+    /// showdown when the betting is done.
+    pub fn apply(&mut self, action: Action) -> Result<(), String> {
+        self.table.apply(action)?;
+        self.advance();
+        Ok(())
+    }
+
+    /// The seat with the best hand showing, which acts first after third street.  Ties go to the player closest
+    /// to the left of the dealer.
+    pub fn best_showing(&self) -> usize {
+        let players = self.table.players();
+        let count = players.len();
+        let mut best: Option<(usize, Showing)> = None;
+        for offset in 1..=count {
+            let seat = (self.dealer + offset) % count;
+            if players[seat].folded {
+                continue;
+            }
+
+            let score = showing(&players[seat].cards.up);
+            let better = match &best {
+                Some((_, current)) => score > *current,
+                None => true
+            };
+
+            if better {
+                best = Some((seat, score));
+            }
+        }
+
+        best.unwrap().0
+    }
+
+    /// Deal the next street, or finish the hand, once nobody has to act.
+    fn advance(&mut self) {
+        loop {
+            match self.table.next(self.street == Street::Seventh) {
+                Next::Act => return,
+                Next::Deal => {
+                    self.deal_street();
+                    let bet_size = match self.street {
+                        Street::Third | Street::Fourth => self.stakes.small_bet,
+                        _ => self.stakes.big_bet
+                    };
+                    self.table.new_round(bet_size);
+                    let count = self.table.players().len();
+                    self.table.round.start((self.best_showing() + count - 1) % count);
+                },
+                Next::Showdown => {
+                    while self.street != Street::Seventh {
+                        self.deal_street();
+                    }
+                    self.finish();
+                    return;
+                },
+                Next::Uncontested => {
+                    self.finish();
+                    return;
+                }
+            }
+        }
+    }
+
+    /// Deal the next street to every player still in the hand, starting to the left of the dealer.  The last
+    /// card is dealt down.
+    fn deal_street(&mut self) {
+        self.street = match self.street {
+            Street::Third => Street::Fourth,
+            Street::Fourth => Street::Fifth,
+            Street::Fifth => Street::Sixth,
+            _ => Street::Seventh
+        };
+
+        let count = self.table.players.len();
+        for offset in 1..=count {
+            let player = &mut self.table.players[(self.dealer + offset) % count];
+            if player.folded {
+                continue;
+            }
+
+            let card = self.deck.deal().unwrap();
+            match self.street {
+                Street::Seventh => player.cards.down.push(card),
+                _ => player.cards.up.push(card)
+            }
+        }
+    }
+
+    /// Split the pot between the best hands, with odd chips to the left of the dealer.
+    fn finish(&mut self) {
+        // Hands are only shown when every card has been dealt, otherwise everyone else folded.
+        let scores: Vec<Option<Score>> = self.table.players().iter().map(|player| {
+            if player.folded || self.street != Street::Seventh {
+                return None;
+            }
+
+            Some(PokerHand::best_of(&player.cards.all()).unwrap().score)
+        }).collect();
+
+        self.table.finish(&scores, self.dealer);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cards::parse_cards;
+    use crate::table::testing::{play, stacked};
+
+    const STAKES: Stakes = Stakes { ante: 1, bring_in: 2, small_bet: 5, big_bet: 10 };
+
+    fn cards(cards: &str) -> Vec<Card> {
+        parse_cards(cards).unwrap()
+    }
+
+    #[test]
+    fn showing_ranks() {
+        assert_eq!(showing(&cards("AsKh")).category(), Category::HighCard);
+        assert_eq!(showing(&cards("2s2h")).category(), Category::Pair);
+        assert_eq!(showing(&cards("2s9h2h")).ranks(), &[Rank::Two, Rank::Two, Rank::Nine][..]);
+        assert_eq!(showing(&cards("9s2h9h2c")).category(), Category::TwoPair);
+        assert_eq!(showing(&cards("9s9h9c2c")).category(), Category::ThreeOfAKind);
+        assert_eq!(showing(&cards("9s9h9c9d")).category(), Category::FourOfAKind);
+
+        assert!(showing(&cards("AsKh")) > showing(&cards("AhQh")));
+        assert!(showing(&cards("2s2h")) > showing(&cards("AhKh")));
+        assert!(showing(&cards("3s3h2c")) > showing(&cards("2s2hAc")));
+        assert!(showing(&cards("3s3h2c2d")) > showing(&cards("AsAhKcQd")));
+        assert!(showing(&cards("2s2h2c4d")) > showing(&cards("AsAhKcKd")));
+        // Straights and flushes don't count.
+        assert_eq!(showing(&cards("AhKhQhJh")).category(), Category::HighCard);
+        assert_eq!(showing(&cards("AhKh")).cmp(&showing(&cards("AsKs"))), Ordering::Equal);
+    }
+
+    #[test]
+    fn third_street_is_dealt_from_the_left_of_the_dealer() {
+        let hand = Hand::new(&[100, 100, 100], 2, STAKES, stacked("AsKsQs JsTs9s 8s7s6s")).unwrap();
+        assert_eq!(hand.players()[0].cards.down, cards("AsJs"));
+        assert_eq!(hand.players()[0].cards.up, cards("8s"));
+        assert_eq!(hand.players()[2].cards.all(), cards("Qs9s6s"));
+        assert_eq!(hand.street(), Street::Third);
+    }
+
+    #[test]
+    fn lowest_up_card_brings_in() {
+        let hand = Hand::new(&[100, 100, 100], 0, STAKES, stacked("AsAhAd KsKhKd 3h2cTc")).unwrap();
+        assert_eq!(hand.bring_in(), 2);
+        assert_eq!(hand.players()[2].bet, 2);
+        assert_eq!(hand.players()[2].invested, 3);
+        assert_eq!(hand.pot(), 5);
+        assert_eq!(hand.to_act(), Some(0));
+        assert_eq!(hand.legal_actions(), vec![Legal::Fold, Legal::Call(2), Legal::Raise{ min: 5, max: 5 }]);
+    }
+
+    #[test]
+    fn bring_in_ties_go_to_the_lowest_suit() {
+        let hand = Hand::new(&[100, 100, 100], 0, STAKES, stacked("AsAhAd KsKhKd 2s2d2h")).unwrap();
+        assert_eq!(hand.bring_in(), 2);
+
+        // The ace is high, so it never brings in against a lower card.
+        let hand = Hand::new(&[100, 100], 0, STAKES, stacked("QsQh KsKh KcAc")).unwrap();
+        assert_eq!(hand.bring_in(), 1);
+    }
+
+    #[test]
+    fn all_in_from_the_ante_does_not_bring_in() {
+        // Seat 2 has the lowest card but only had chips for the ante, so seat 1 with the three brings in.
+        let mut hand = Hand::new(&[100, 100, 1], 0, STAKES, stacked("AsAhAd KsKhKd 3h2cTc")).unwrap();
+        assert!(hand.players()[2].is_all_in());
+        assert_eq!(hand.bring_in(), 1);
+        assert_eq!(hand.players()[1].bet, 2);
+        assert_eq!(hand.to_act(), Some(0));
+        assert_eq!(hand.legal_actions(), vec![Legal::Fold, Legal::Call(2), Legal::Raise{ min: 5, max: 5 }]);
+
+        // Seat 1 completes, seat 0 calls and the all in player is still in for the antes.
+        play(&mut hand, &[Action::Call, Action::Raise(5), Action::Call]);
+        assert_eq!(hand.street(), Street::Fourth);
+        assert_eq!(hand.pot(), 13);
+        assert!(!hand.players()[2].folded);
+    }
+
+    #[test]
+    fn completing_the_bring_in() {
+        let mut hand = Hand::new(&[100, 100, 100], 0, STAKES, stacked("AsAhAd KsKhKd 3h2cTc")).unwrap();
+        play(&mut hand, &[Action::Raise(5)]);
+        assert_eq!(hand.legal_actions(), vec![Legal::Fold, Legal::Call(5), Legal::Raise{ min: 10, max: 10 }]);
+        play(&mut hand, &[Action::Raise(10), Action::Call, Action::Call]);
+        assert_eq!(hand.street(), Street::Fourth);
+        assert_eq!(hand.pot(), 33);
+    }
+
+    #[test]
+    fn everyone_folds_to_the_bring_in() {
+        let mut hand = Hand::new(&[100, 100, 100], 0, STAKES, stacked("AsAhAd KsKhKd 3h2cTc")).unwrap();
+        play(&mut hand, &[Action::Fold, Action::Fold]);
+        assert!(hand.is_over());
+        assert_eq!(hand.winnings(), Some(&[0, 0, 3][..]));
+        assert_eq!(hand.players().iter().map(|player| player.stack).collect::<Vec<u64>>(), vec![99, 99, 102]);
+        assert_eq!(hand.apply(Action::Check), Err(String::from("The hand is over.")));
+    }
+
+    #[test]
+    fn best_hand_showing_acts_first() {
+        // Seat 0 brings in with the deuce, then seat 2 pairs its eight on fourth street.
+        let deck = stacked("AsAhAd KsKhKd 3h8c2c 4d8d5s");
+        let mut hand = Hand::new(&[100, 100, 100], 0, STAKES, deck).unwrap();
+        play(&mut hand, &[Action::Call, Action::Call, Action::Check]);
+        assert_eq!(hand.street(), Street::Fourth);
+        assert_eq!(hand.players()[2].cards.up, cards("8c8d"));
+        assert_eq!(hand.best_showing(), 2);
+        assert_eq!(hand.to_act(), Some(2));
+        assert_eq!(hand.legal_actions(), vec![Legal::Check, Legal::Bet{ min: 5, max: 5 }]);
+    }
+
+    #[test]
+    fn best_showing_ties_go_left_of_the_dealer() {
+        let deck = stacked("AsAhAd KsKhKd 3h8c2c 8h3d5s");
+        let mut hand = Hand::new(&[100, 100, 100], 1, STAKES, deck).unwrap();
+        play(&mut hand, &[Action::Call, Action::Call, Action::Check]);
+        // Seats 0 and 2 both show 8-3 and seat 2 is first to the left of the dealer.
+        assert_eq!(hand.players()[0].cards.up, cards("8c3d"));
+        assert_eq!(hand.players()[2].cards.up, cards("3h8h"));
+        assert_eq!(hand.best_showing(), 2);
+        assert_eq!(hand.to_act(), Some(2));
+    }
+
+    #[test]
+    fn big_bets_from_fifth_street() {
+        let mut hand = Hand::new(&[100, 100], 0, STAKES, Deck::new()).unwrap();
+        play(&mut hand, &[Action::Call, Action::Check]);
+        assert_eq!(hand.street(), Street::Fourth);
+        play(&mut hand, &[Action::Check, Action::Check]);
+        assert_eq!(hand.street(), Street::Fifth);
+        assert_eq!(hand.legal_actions(), vec![Legal::Check, Legal::Bet{ min: 10, max: 10 }]);
+    }
+
+    #[test]
+    fn showdown_with_the_best_five_of_seven() {
+        // Seat 1 has trip kings, seat 0 has a pair of aces and both seventh street cards are down.
+        let deck = stacked("KsAs KhAh 2c3d 7c8d 9c4d Jd6s Kd5h");
+        let mut hand = Hand::new(&[100, 100], 0, STAKES, deck).unwrap();
+        assert_eq!(hand.bring_in(), 1);
+        play(&mut hand, &[Action::Call, Action::Check]);
+        for _ in 0..4 {
+            play(&mut hand, &[Action::Check, Action::Check]);
+        }
+        assert!(hand.is_over());
+        assert_eq!(hand.players()[1].cards.down, cards("KsKhKd"));
+        assert_eq!(hand.players()[1].cards.up.len(), 4);
+        assert_eq!(hand.winnings(), Some(&[0, 6][..]));
+    }
+
+    #[test]
+    fn all_in_deals_every_street() {
+        // Seat 0 brings in and seat 1 has 3 chips left after the ante.
+        let mut hand = Hand::new(&[100, 4], 0, STAKES, stacked("AsAh KsKh 3c2c")).unwrap();
+        assert_eq!(hand.bring_in(), 0);
+        assert_eq!(hand.to_act(), Some(1));
+        play(&mut hand, &[Action::AllIn]);
+        assert_eq!(hand.legal_actions(), vec![Legal::Fold, Legal::Call(1)]);
+        play(&mut hand, &[Action::Call]);
+        assert!(hand.is_over());
+        assert!(hand.players().iter().all(|player| player.cards.all().len() == 7));
+        assert_eq!(hand.winnings().unwrap().iter().sum::<u64>(), 8);
+    }
+
+    #[test]
+    fn bad_setup() {
+        assert_eq!(Hand::new(&[100; 8], 0, STAKES, Deck::new()), Err(String::from("Required 2 to 7 players but found 8.")));
+        assert_eq!(Hand::new(&[100, 100], 2, STAKES, Deck::new()), Err(String::from("Seat 2 is not at the table.")));
+        assert_eq!(Hand::new(&[100, 0], 0, STAKES, Deck::new()), Err(String::from("Seat 1 has no chips.")));
+        let stakes = Stakes { bring_in: 6, ..STAKES };
+        assert!(Hand::new(&[100, 100], 0, stakes, Deck::new()).is_err());
+        assert_eq!(Hand::new(&[100, 100], 0, STAKES, Deck::from_cards(cards("AsKs"))), Err(String::from("Required 14 cards but only 2 remain.")));
+    }
+}
@@ -0,0 +1,280 @@
+//! # table
+//!
+//! `table` keeps the chips for the game engines in `holdem` and `stud`: each player's stack and bets, the
+//! betting round for the street and the pot, which is split in to side pots at the end of the hand.  The
+//! engines deal the cards and say who acts first on each street.
+
+use crate::betting::{Action, Legal, Limit, Round};
+use crate::pot::{award, side_pots};
+
+/// A player in the hand, holding the cards of the game being played.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Player<C> {
+    /// The chips the player has left behind.
+    pub stack: u64,
+    pub cards: C,
+    /// The chips bet on this street.
+    pub bet: u64,
+    /// The chips put in to the pot on every street, including the ante.
+    pub invested: u64,
+    pub folded: bool
+}
+
+impl<C> Player<C> {
+    /// A player with a stack who hasn't put anything in yet.
+    pub fn new(stack: u64, cards: C) -> Player<C> {
+        Player { stack, cards, bet: 0, invested: 0, folded: false }
+    }
+
+    pub fn is_all_in(&self) -> bool {
+        !self.folded && self.stack == 0
+    }
+
+    fn can_act(&self) -> bool {
+        !self.folded && self.stack > 0
+    }
+}
+
+/// What the game does after an action.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub(crate) enum Next {
+    /// Wait for the player to act.
+    Act,
+    /// Deal the next street and start its betting.
+    Deal,
+    /// Deal any streets left and show down, because nobody can bet any more.
+    Showdown,
+    /// Give the pot to the last player left.
+    Uncontested
+}
+
+/// The players and the chips in a hand.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub(crate) struct Table<C> {
+    pub(crate) players: Vec<Player<C>>,
+    /// The betting on this street.
+    pub(crate) round: Round,
+    limit: Limit,
+    bet_size: u64,
+    // The chips from earlier streets and antes, not counting bets on this street.
+    pot: u64,
+    winnings: Option<Vec<u64>>
+}
+
+impl<C> Table<C> {
+    /// Seat the players, post the antes and open the first street's betting with `bet_size`.  Forced bets are
+    /// posted on the round by the game, which then calls `sync`.
+    pub(crate) fn new(mut players: Vec<Player<C>>, ante: u64, limit: Limit, bet_size: u64) -> Table<C> {
+        for player in players.iter_mut() {
+            let ante = ante.min(player.stack);
+            player.stack -= ante;
+            player.invested += ante;
+        }
+
+        let pot = players.iter().map(|player| player.invested).sum();
+        Table {
+            round: betting_round(&players, limit, bet_size, pot),
+            players,
+            limit,
+            bet_size,
+            pot,
+            winnings: None
+        }
+    }
+
+    pub(crate) fn players(&self) -> &[Player<C>] {
+        &self.players
+    }
+
+    /// All the chips in the middle, including bets on this street.
+    pub(crate) fn pot(&self) -> u64 {
+        self.pot + self.players.iter().map(|player| player.bet).sum::<u64>()
+    }
+
+    pub(crate) fn is_over(&self) -> bool {
+        self.winnings.is_some()
+    }
+
+    pub(crate) fn winnings(&self) -> Option<&[u64]> {
+        self.winnings.as_deref()
+    }
+
+    pub(crate) fn legal_actions(&self) -> Vec<Legal> {
+        self.round.legal_actions()
+    }
+
+    /// Take an action for the player to act.  The game calls `next` afterwards to move the hand on.
+    pub(crate) fn apply(&mut self, action: Action) -> Result<(), String> {
+        if self.is_over() {
+            return Err(String::from("The hand is over."));
+        }
+
+        self.round.apply(action)?;
+        self.sync();
+        Ok(())
+    }
+
+    /// Replace the round with a new street's betting between the players still in the hand.
+    pub(crate) fn new_round(&mut self, bet_size: u64) {
+        self.bet_size = bet_size;
+        self.round = betting_round(&self.players, self.limit, bet_size, self.pot);
+    }
+
+    /// Copy the stacks, bets and folds from the betting round to the players.
+    pub(crate) fn sync(&mut self) {
+        for (seat, player) in self.players.iter_mut().enumerate() {
+            player.invested = player.invested - player.bet + self.round.bets()[seat];
+            player.bet = self.round.bets()[seat];
+            player.stack = self.round.stacks()[seat];
+            player.folded = self.round.folded()[seat];
+        }
+    }
+
+    /// End the street once nobody has to act and say what comes next.  There is no more betting after the
+    /// `last_street`, or once fewer than two players have chips left.
+    pub(crate) fn next(&mut self, last_street: bool) -> Next {
+        if self.players.iter().filter(|player| !player.folded).count() == 1 {
+            return Next::Uncontested;
+        }
+
+        if !self.round.is_complete() {
+            return Next::Act;
+        }
+
+        self.end_street();
+        if last_street || self.players.iter().filter(|player| player.can_act()).count() < 2 {
+            Next::Showdown
+        } else {
+            Next::Deal
+        }
+    }
+
+    /// Award the main pot and each side pot to the best eligible hand, or everything to the last player left.
+    /// Odd chips go to the winners closest to the left of the `button`.
+    pub(crate) fn finish<T: Ord>(&mut self, scores: &[Option<T>], button: usize) {
+        self.end_street();
+
+        let contributions: Vec<u64> = self.players.iter().map(|player| player.invested).collect();
+        let folded: Vec<bool> = self.players.iter().map(|player| player.folded).collect();
+        let winnings = award(&side_pots(&contributions, &folded), scores, button);
+        for (player, won) in self.players.iter_mut().zip(&winnings) {
+            player.stack += won;
+        }
+
+        self.pot = 0;
+        self.winnings = Some(winnings);
+    }
+
+    /// Return any part of the biggest bet that nobody called and move the bets in to the pot.  Nobody acts
+    /// until the game starts the next street.
+    fn end_street(&mut self) {
+        self.round.return_uncalled();
+        self.sync();
+        for player in self.players.iter_mut() {
+            self.pot += player.bet;
+            player.bet = 0;
+        }
+        self.new_round(self.bet_size);
+    }
+}
+
+/// A betting round between the players, after `pot` chips from earlier streets and antes.
+fn betting_round<C>(players: &[Player<C>], limit: Limit, bet_size: u64, pot: u64) -> Round {
+    let stacks: Vec<u64> = players.iter().map(|player| player.stack).collect();
+    let folded: Vec<bool> = players.iter().map(|player| player.folded).collect();
+    Round::new(limit, bet_size, pot, &stacks, &folded)
+}
+
+/// Helpers shared by the tests of the betting and the game engines.
+#[cfg(test)]
+pub(crate) mod testing {
+    use crate::betting::{Action, Round};
+    use crate::cards::{parse_cards, Card, Deck};
+    use crate::{holdem, stud};
+
+    /// Anything that takes actions one at a time.
+    pub(crate) trait Play {
+        fn apply(&mut self, action: Action) -> Result<(), String>;
+    }
+
+    impl Play for Round {
+        fn apply(&mut self, action: Action) -> Result<(), String> {
+            Round::apply(self, action)
+        }
+    }
+
+    impl Play for holdem::Hand {
+        fn apply(&mut self, action: Action) -> Result<(), String> {
+            holdem::Hand::apply(self, action)
+        }
+    }
+
+    impl Play for stud::Hand {
+        fn apply(&mut self, action: Action) -> Result<(), String> {
+            stud::Hand::apply(self, action)
+        }
+    }
+
+    /// A deck that deals `cards` first, in order, followed by the rest of the deck.
+    pub(crate) fn stacked(cards: &str) -> Deck {
+        let mut order = parse_cards(cards).unwrap();
+        order.extend(Card::all().into_iter().filter(|card| !order.contains(card)).collect::<Vec<Card>>());
+        order.reverse();
+        Deck::from_cards(order)
+    }
+
+    pub(crate) fn play<P: Play>(game: &mut P, actions: &[Action]) {
+        for action in actions {
+            game.apply(*action).unwrap();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn table(stacks: &[u64], ante: u64) -> Table<()> {
+        Table::new(stacks.iter().map(|stack| Player::new(*stack, ())).collect(), ante, Limit::NoLimit, 10)
+    }
+
+    #[test]
+    fn antes_go_in_the_pot() {
+        let table = table(&[100, 1, 50], 2);
+        assert_eq!(table.pot(), 5);
+        assert_eq!(table.players().iter().map(|player| player.stack).collect::<Vec<u64>>(), vec![98, 0, 48]);
+        assert!(table.players()[1].is_all_in());
+        assert_eq!(table.round.pot(), 5);
+    }
+
+    #[test]
+    fn uncalled_bet_comes_back_before_the_pot_is_split() {
+        let mut table = table(&[100, 40], 0);
+        table.round.start(1);
+        table.apply(Action::Bet(100)).unwrap();
+        table.apply(Action::Call).unwrap();
+        assert_eq!(table.next(false), Next::Showdown);
+        assert_eq!(table.players().iter().map(|player| player.invested).collect::<Vec<u64>>(), vec![40, 40]);
+        assert_eq!(table.pot(), 80);
+
+        table.finish(&[Some(1), Some(2)], 0);
+        assert!(table.is_over());
+        assert_eq!(table.winnings(), Some(&[0, 80][..]));
+        assert_eq!(table.players()[0].stack, 60);
+        assert_eq!(table.apply(Action::Check), Err(String::from("The hand is over.")));
+    }
+
+    #[test]
+    fn last_player_left() {
+        let mut table = table(&[100, 100, 100], 1);
+        table.round.start(2);
+        table.apply(Action::Bet(10)).unwrap();
+        assert_eq!(table.next(false), Next::Act);
+        table.apply(Action::Fold).unwrap();
+        table.apply(Action::Fold).unwrap();
+        assert_eq!(table.next(false), Next::Uncontested);
+
+        table.finish::<u8>(&[None, None, None], 0);
+        assert_eq!(table.winnings(), Some(&[3, 0, 0][..]));
+    }
+}
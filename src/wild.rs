@@ -0,0 +1,303 @@
+//! # wild
+//!
+//! `wild` scores hands with wild cards, such as jokers and "deuces wild", by trying every card each wild could
+//! stand for and keeping the best hand.
+//!
+//! A wild card stands for a card that isn't already in the hand when there is one, so a wild card with four
+//! hearts can't be a second ace of hearts.  Only when every suit of a rank is taken does it repeat a card, so
+//! four aces and a wild card are five aces.  The bug is a joker that may only be an ace or complete a straight
+//! or a flush.
+
+use crate::cards::{Card, DeckCard, Rank, Suit};
+use crate::{for_each_combination, Category, PokerHand, Score};
+
+/// How jokers play.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum Joker {
+    /// The joker stands for any card.
+    Wild,
+    /// The joker is an ace unless it completes a straight or a flush.
+    Bug
+}
+
+/// Which cards are wild.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct WildRules {
+    /// Every card of these ranks is wild.
+    pub ranks: Vec<Rank>,
+    pub joker: Joker
+}
+
+impl WildRules {
+    /// Only jokers are wild.
+    pub fn jokers() -> WildRules {
+        WildRules { ranks: Vec::new(), joker: Joker::Wild }
+    }
+
+    /// Deuces and jokers are wild.
+    pub fn deuces_wild() -> WildRules {
+        WildRules { ranks: vec![Rank::Two], joker: Joker::Wild }
+    }
+
+    /// Jokers are the bug and nothing else is wild.
+    pub fn bug() -> WildRules {
+        WildRules { ranks: Vec::new(), joker: Joker::Bug }
+    }
+
+    /// True if the card is wild under these rules.
+    pub fn is_wild(&self, card: &DeckCard) -> bool {
+        match card {
+            DeckCard::Card(card) => self.ranks.contains(&card.rank),
+            DeckCard::Joker => true
+        }
+    }
+}
+
+/// The best hand made with wild cards, the cards it is made of and the card each wild became.
+#[derive(Debug, PartialEq, Clone)]
+pub struct WildHand {
+    pub score: Score,
+    pub cards: [Card; 5],
+    /// Each wild card in the hand, in order, and the card it stands for.
+    pub substitutions: Vec<(DeckCard, Card)>
+}
+
+/// Score five cards that may include wild cards.
+///
+/// # Examples
+/// ```
+/// use poker::cards::{Card, DeckCard};
+/// use poker::wild::{score, WildRules};
+/// use poker::Score;
+///
+/// let cards = ["2h", "Ks", "Kd", "Kc", "7h"].map(|card| card.parse::<DeckCard>().unwrap());
+/// let hand = score(&cards, &WildRules::deuces_wild()).unwrap();
+/// assert_eq!(hand.score, Score::FourOfAKind{ four_of_a_kind: poker::cards::Rank::King, kicker: poker::cards::Rank::Seven });
+/// assert_eq!(hand.substitutions, vec![(cards[0], "Kh".parse::<Card>().unwrap())]);
+/// ```
+pub fn score(cards: &[DeckCard; 5], rules: &WildRules) -> Result<WildHand, String> {
+    check_distinct(cards)?;
+
+    let mut hand = [Card::new(Rank::Ace, Suit::Spades); 5];
+    let mut naturals: Vec<Card> = Vec::new();
+    let mut wilds: Vec<usize> = Vec::new();
+    for (idx, card) in cards.iter().enumerate() {
+        match card {
+            DeckCard::Card(natural) if !rules.is_wild(card) => {
+                hand[idx] = *natural;
+                naturals.push(*natural);
+            },
+            _ => wilds.push(idx)
+        }
+    }
+
+    // Suits only matter for a flush, so when the natural cards share a suit each wild takes it if that card isn't
+    // already in the hand, and otherwise the first suit that isn't.
+    let flush_suit = match naturals.first() {
+        Some(first) if naturals.iter().all(|card| card.suit == first.suit) => Some(first.suit),
+        Some(_) => None,
+        None => Some(Suit::Spades)
+    };
+
+    let mut best: Option<WildHand> = None;
+    let mut choice = vec![0; wilds.len()];
+    loop {
+        let mut used = naturals.clone();
+        for (wild, idx) in wilds.iter().enumerate() {
+            let rank = Rank::ALL[choice[wild]];
+            let unused = |suit: &Suit| !used.contains(&Card::new(rank, *suit));
+            let suit = flush_suit.filter(unused)
+                .or_else(|| Suit::ALL.iter().copied().find(unused))
+                .unwrap_or(Suit::Spades);
+            hand[*idx] = Card::new(rank, suit);
+            used.push(hand[*idx]);
+        }
+
+        let score = PokerHand::new(hand).score();
+        let completes = matches!(score.category(), Category::Straight | Category::Flush | Category::StraightFlush);
+        let allowed = wilds.iter().all(|idx| {
+            rules.joker == Joker::Wild || cards[*idx] != DeckCard::Joker || hand[*idx].rank == Rank::Ace || completes
+        });
+        let better = match &best {
+            Some(current) => score > current.score,
+            None => true
+        };
+
+        if allowed && better {
+            let substitutions = wilds.iter().map(|idx| (cards[*idx], hand[*idx])).collect();
+            best = Some(WildHand { score, cards: hand, substitutions });
+        }
+
+        // Move on to the next choice of ranks, like an odometer.
+        let mut wild = 0;
+        loop {
+            if wild == choice.len() {
+                // The bug can always be an ace, so there is always a hand.
+                return Ok(best.unwrap());
+            }
+
+            choice[wild] += 1;
+            if choice[wild] < Rank::ALL.len() {
+                break;
+            }
+            choice[wild] = 0;
+            wild += 1;
+        }
+    }
+}
+
+/// Find the best hand from 5 to 7 cards that may include wild cards.
+///
+/// # Examples
+/// ```
+/// use poker::cards::DeckCard;
+/// use poker::wild::{best_of, WildRules};
+/// use poker::Score;
+///
+/// let cards: Vec<DeckCard> = ["Jk", "9h", "8h", "7h", "6h", "Ac", "Ad"].iter().map(|card| card.parse().unwrap()).collect();
+/// let hand = best_of(&cards, &WildRules::bug()).unwrap();
+/// assert_eq!(hand.score, Score::StraightFlush(poker::cards::Rank::Ten));
+/// ```
+pub fn best_of(cards: &[DeckCard], rules: &WildRules) -> Result<WildHand, String> {
+    if cards.len() < 5 || cards.len() > 7 {
+        return Err(format!("Required 5 to 7 cards but found {}.", cards.len()));
+    }
+
+    check_distinct(cards)?;
+
+    let mut best: Option<WildHand> = None;
+    for_each_combination(cards.len(), 5, |idx| {
+        let hand = score(&[cards[idx[0]], cards[idx[1]], cards[idx[2]], cards[idx[3]], cards[idx[4]]], rules).unwrap();
+        let better = match &best {
+            Some(current) => hand.score > current.score,
+            None => true
+        };
+
+        if better {
+            best = Some(hand);
+        }
+    });
+
+    Ok(best.unwrap())
+}
+
+/// Jokers may repeat but other cards may not.
+fn check_distinct(cards: &[DeckCard]) -> Result<(), String> {
+    for (idx, card) in cards.iter().enumerate() {
+        if *card != DeckCard::Joker && cards[..idx].contains(card) {
+            return Err(format!("Card '{}' appears more than once.", card));
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn deck_cards(cards: &str) -> Vec<DeckCard> {
+        cards.split_whitespace().map(|card| card.parse().unwrap()).collect()
+    }
+
+    fn five(cards: &str, rules: &WildRules) -> WildHand {
+        let cards = deck_cards(cards);
+        score(&[cards[0], cards[1], cards[2], cards[3], cards[4]], rules).unwrap()
+    }
+
+    fn card(card: &str) -> Card {
+        card.parse().unwrap()
+    }
+
+    #[test]
+    fn no_wild_cards() {
+        let hand = five("Ah Kh Qh Jh 9h", &WildRules::deuces_wild());
+        assert_eq!(hand.score, PokerHand::from_str("AhKhQhJh9h").unwrap().score());
+        assert!(hand.substitutions.is_empty());
+
+        let hand = five("2h 2d Qh Jh 9h", &WildRules::jokers());
+        assert_eq!(hand.score.category(), Category::Pair);
+    }
+
+    #[test]
+    fn joker_completes_a_royal_flush() {
+        let hand = five("Jk Kh Qh Jh Th", &WildRules::jokers());
+        assert_eq!(hand.score, Score::StraightFlush(Rank::Ace));
+        assert_eq!(hand.substitutions, vec![(DeckCard::Joker, card("Ah"))]);
+        assert_eq!(hand.cards[0], card("Ah"));
+    }
+
+    #[test]
+    fn deuces_wild() {
+        let hand = five("2h As Ad Ac Kd", &WildRules::deuces_wild());
+        assert_eq!(hand.score, Score::FourOfAKind{ four_of_a_kind: Rank::Ace, kicker: Rank::King });
+        assert_eq!(hand.substitutions, vec![(DeckCard::Card(card("2h")), card("Ah"))]);
+
+        let hand = five("2h 2d 2c 2s 9d", &WildRules::deuces_wild());
+        assert_eq!(hand.score, Score::FiveOfAKind(Rank::Nine));
+
+        let hand = five("2h 2d 7c 8s 9d", &WildRules::deuces_wild());
+        assert_eq!(hand.score, Score::Straight(Rank::Jack));
+
+        let hand = five("2h 2d 7c 7s 9d", &WildRules::deuces_wild());
+        assert_eq!(hand.score, Score::FourOfAKind{ four_of_a_kind: Rank::Seven, kicker: Rank::Nine });
+    }
+
+    #[test]
+    fn wild_is_not_a_card_already_held() {
+        // The joker can't be a second ace of hearts, so the best flush is ace-king.
+        let hand = five("Jk Ah 7h 9h Jh", &WildRules::jokers());
+        assert_eq!(hand.score, Score::Flush{ card: Rank::Ace, kickers: [Rank::Seven, Rank::Nine, Rank::Jack, Rank::King] });
+        assert_eq!(hand.substitutions, vec![(DeckCard::Joker, card("Kh"))]);
+        assert_eq!(hand.score, PokerHand::from_str("AhKh7h9hJh").unwrap().score());
+
+        // A pair of aces can still be made with an ace of another suit.
+        let hand = five("Jk Ah 7c 9h Jh", &WildRules::jokers());
+        assert_eq!(hand.score.category(), Category::Pair);
+        assert_ne!(hand.substitutions[0].1, card("Ah"));
+    }
+
+    #[test]
+    fn every_card_wild() {
+        let hand = five("2h 2d 2c 2s Jk", &WildRules::deuces_wild());
+        assert_eq!(hand.score, Score::FiveOfAKind(Rank::Ace));
+        assert_eq!(hand.substitutions.len(), 5);
+    }
+
+    #[test]
+    fn bug_is_an_ace() {
+        // The bug can't be a fourth king.
+        let hand = five("Jk Ks Kd Kc 2h", &WildRules::bug());
+        assert_eq!(hand.score, Score::ThreeOfAKind{ card: Rank::King, high_kicker: Rank::Ace, low_kicker: Rank::Two });
+
+        let hand = five("Jk As Ad 3c 4h", &WildRules::bug());
+        assert_eq!(hand.score, Score::ThreeOfAKind{ card: Rank::Ace, high_kicker: Rank::Four, low_kicker: Rank::Three });
+    }
+
+    #[test]
+    fn bug_completes_straights_and_flushes() {
+        let hand = five("Jk 2c 3d 4h 5s", &WildRules::bug());
+        assert_eq!(hand.score, Score::Straight(Rank::Six));
+        assert_eq!(hand.substitutions[0].1.rank, Rank::Six);
+
+        let hand = five("Jk 2h 7h 9h Jh", &WildRules::bug());
+        assert_eq!(hand.score, Score::Flush{ card: Rank::Ace, kickers: [Rank::Two, Rank::Seven, Rank::Nine, Rank::Jack] });
+
+        let hand = five("Jk 9h 8h 7h 6h", &WildRules::bug());
+        assert_eq!(hand.score, Score::StraightFlush(Rank::Ten));
+    }
+
+    #[test]
+    fn best_of_seven_with_jokers() {
+        let hand = best_of(&deck_cards("Jk Jk As Ad Kc 7h 3d"), &WildRules::jokers()).unwrap();
+        assert_eq!(hand.score, Score::FourOfAKind{ four_of_a_kind: Rank::Ace, kicker: Rank::King });
+        assert_eq!(hand.substitutions.len(), 2);
+    }
+
+    #[test]
+    fn bad_input() {
+        assert_eq!(best_of(&deck_cards("Jk As Ad Kc"), &WildRules::jokers()), Err(String::from("Required 5 to 7 cards but found 4.")));
+        let cards = deck_cards("As As Ad Kc Jk");
+        assert_eq!(score(&[cards[0], cards[1], cards[2], cards[3], cards[4]], &WildRules::jokers()), Err(String::from("Card 'As' appears more than once.")));
+    }
+}
@@ -0,0 +1,40 @@
+use poker::{Category, PokerHand, Score};
+use poker::cards::Rank;
+use poker::enumerate::{five_card_hands, rank_multisets, tally};
+
+    #[test]
+    fn test_published_frequencies() {
+        let scores: Vec<Score> = five_card_hands().map(|cards| PokerHand::new(cards).score()).collect();
+        let royals = scores.iter().filter(|score| **score == Score::StraightFlush(Rank::Ace)).count();
+        let counts = tally(scores);
+
+        assert_eq!(royals, 4);
+
+        assert_eq!(counts.get(&Category::StraightFlush), Some(&40));
+        assert_eq!(counts.get(&Category::FourOfAKind), Some(&624));
+        assert_eq!(counts.get(&Category::FullHouse), Some(&3_744));
+        assert_eq!(counts.get(&Category::Flush), Some(&5_108));
+        assert_eq!(counts.get(&Category::Straight), Some(&10_200));
+        assert_eq!(counts.get(&Category::ThreeOfAKind), Some(&54_912));
+        assert_eq!(counts.get(&Category::TwoPair), Some(&123_552));
+        assert_eq!(counts.get(&Category::Pair), Some(&1_098_240));
+        assert_eq!(counts.get(&Category::HighCard), Some(&1_302_540));
+        assert_eq!(counts.get(&Category::FiveOfAKind), None);
+        assert_eq!(counts.values().sum::<u64>(), 2_598_960);
+    }
+
+    #[test]
+    fn test_suitless_frequencies() {
+        let counts = tally(rank_multisets().map(|ranks| PokerHand::from_ranks(ranks).score()));
+
+        assert_eq!(counts.get(&Category::FiveOfAKind), Some(&13));
+        assert_eq!(counts.get(&Category::FourOfAKind), Some(&156));
+        assert_eq!(counts.get(&Category::FullHouse), Some(&156));
+        assert_eq!(counts.get(&Category::Straight), Some(&10));
+        assert_eq!(counts.get(&Category::ThreeOfAKind), Some(&858));
+        assert_eq!(counts.get(&Category::TwoPair), Some(&858));
+        assert_eq!(counts.get(&Category::Pair), Some(&2_860));
+        assert_eq!(counts.get(&Category::HighCard), Some(&1_277));
+        assert_eq!(counts.get(&Category::Flush), None);
+        assert_eq!(counts.values().sum::<u64>(), 6_188);
+    }
@@ -0,0 +1,67 @@
+use poker::PokerHand;
+use poker::cards::Card;
+use poker::compare_hands;
+use poker::fast;
+
+use std::cmp::Ordering;
+
+    #[test]
+    fn test_every_five_card_hand() {
+        let deck = Card::all();
+        let mut count = 0;
+        for a in 0..52 {
+            for b in a + 1..52 {
+                for c in b + 1..52 {
+                    for d in c + 1..52 {
+                        for e in d + 1..52 {
+                            let cards = [deck[a], deck[b], deck[c], deck[d], deck[e]];
+                            let rank = fast::score(&cards).unwrap();
+                            assert_eq!(rank.score(), PokerHand::new(cards).score());
+                            count += 1;
+                        }
+                    }
+                }
+            }
+        }
+
+        assert_eq!(count, 2_598_960);
+    }
+
+    #[test]
+    fn test_ranks_compare_like_scores() {
+        let hands = ["AsAhKsKdKc", "2c3d4h5s6c", "Kc2s2dAc5h", "3h3sAd4c7d", "AhJh9h4h2h", "AcJc9c5c2c", "AsKsQsJsTs"];
+        for left in hands.iter() {
+            for right in hands.iter() {
+                let left = PokerHand::from_str(left).unwrap();
+                let right = PokerHand::from_str(right).unwrap();
+                let left_rank = fast::score(&left.cards().unwrap()).unwrap();
+                let right_rank = fast::score(&right.cards().unwrap()).unwrap();
+
+                assert_eq!(right_rank.cmp(&left_rank), compare_hands(&left.score(), &right.score()));
+            }
+        }
+    }
+
+    #[test]
+    fn test_seven_card_hands() {
+        let deck = Card::all();
+
+        // A fixed walk through the deck rather than every one of the 133 million hands.
+        let mut state: u64 = 0x2545_F491_4F6C_DD1D;
+        for _ in 0..20_000 {
+            let mut cards: Vec<Card> = Vec::new();
+            while cards.len() < 7 {
+                state ^= state << 13;
+                state ^= state >> 7;
+                state ^= state << 17;
+                let card = deck[(state % 52) as usize];
+                if !cards.contains(&card) {
+                    cards.push(card);
+                }
+            }
+
+            let best = PokerHand::best_of(&cards).unwrap();
+            assert_eq!(fast::best_of(&cards).unwrap().score(), best.score);
+            assert_eq!(compare_hands(&fast::best_of(&cards).unwrap().score(), &best.score), Ordering::Equal);
+        }
+    }
@@ -1,80 +1,81 @@
-use poker::PokerHand;
-use poker::Score;
-use poker::cards::Card;
+use poker::{Category, PokerHand, Score};
+use poker::cards::Rank;
 use poker::compare_hands;
+use poker::enumerate::rank_multisets;
 
 use std::cmp::Ordering;
 
-    #[test]
-    fn test_sample1() {
-        let hand1 = &PokerHand::from_str("AAKKK").unwrap();
-        let hand2 = &PokerHand::from_str("23456").unwrap();
+    /// The rank's sequence with the ace high, 2 to 14.
+    fn high(rank: &Rank) -> usize {
+        match rank {
+            Rank::Ace => 14,
+            other => Rank::ALL.iter().position(|r| r == other).unwrap() + 1
+        }
+    }
 
-        assert_eq!(hand1.score(), Score::FullHouse{pair:Card::Ace, three_of_a_kind:Card::King});
-        assert_eq!(hand2.score(), Score::Straight(Card::Six));
-        assert_eq!(compare_hands(&hand1.score(), &hand2.score()), Ordering::Less);
+    /// The ranks grouped by how many there are, then from highest to lowest, the order a hand is compared in.
+    fn grouped(ranks: &[Rank; 5]) -> Vec<usize> {
+        let seqs: Vec<usize> = ranks.iter().map(high).collect();
+        let mut keyed: Vec<(usize, usize)> = seqs.iter().map(|seq| (seqs.iter().filter(|s| *s == seq).count(), *seq)).collect();
+        keyed.sort_by(|left, right| right.cmp(left));
+        keyed.into_iter().map(|(_, seq)| seq).collect()
     }
 
-    #[test]
-    fn test_sample2() {
-        let hand1 = &PokerHand::from_str("KA225").unwrap();
-        let hand1_score = hand1.score();
-        assert_eq!(hand1_score, Score::Pair{card:Card::Two, kickers:[Card::Five, Card::King, Card::Ace]});
-        
-        let hand2 = &PokerHand::from_str("33A47").unwrap();
-        let hand2_score = hand2.score();
-        assert_eq!(hand2_score, Score::Pair{card:Card::Three, kickers:[Card::Four, Card::Seven, Card::Ace]});
-        
-        assert_eq!(compare_hands(&hand1_score, &hand2_score), Ordering::Greater);
+    fn score(ranks: [Rank; 5]) -> Score {
+        PokerHand::from_ranks(ranks).score()
     }
 
     #[test]
-    fn test_sample3() {
-        let hand1 = &PokerHand::from_str("AA225").unwrap();
-        let hand1_score = hand1.score();
-        assert_eq!(hand1_score, Score::TwoPair{ low_pair:Card::Two, high_pair:Card::Ace, kicker:Card::Five});
-        
-        let hand2 = &PokerHand::from_str("44465").unwrap();
-        let hand2_score = hand2.score();
-        assert_eq!(hand2_score, Score::ThreeOfAKind{card:Card::Four, high_kicker:Card::Six, low_kicker:Card::Five});
+    fn test_order_does_not_matter() {
+        for ranks in rank_multisets() {
+            let mut reversed = ranks;
+            reversed.reverse();
+            let mut rotated = ranks;
+            rotated.rotate_left(2);
 
-        assert_eq!(compare_hands(&hand1_score, &hand2_score), Ordering::Greater);
+            assert_eq!(score(reversed), score(ranks), "{:?}", ranks);
+            assert_eq!(score(rotated), score(ranks), "{:?}", ranks);
+        }
     }
 
     #[test]
-    fn test_sample4() {
-        let hand1 = &PokerHand::from_str("TT8A9").unwrap();
-        let hand1_score = hand1.score();
-        assert_eq!(hand1_score, Score::Pair{card:Card::Ten, kickers:[Card::Eight, Card::Nine, Card::Ace]});
+    fn test_every_hand_is_different_and_ordered_by_category() {
+        let mut scores: Vec<Score> = rank_multisets().map(score).collect();
+        scores.sort();
 
-        let hand2 = &PokerHand::from_str("TTA89").unwrap();
-        let hand2_score = hand2.score();
-        assert_eq!(hand2_score, Score::Pair{card:Card::Ten, kickers:[Card::Eight, Card::Nine, Card::Ace]});
+        for pair in scores.windows(2) {
+            assert_eq!(pair[0].cmp(&pair[1]), Ordering::Less, "{:?}", pair);
+            assert_eq!(compare_hands(&pair[1], &pair[0]), Ordering::Less, "{:?}", pair);
+            assert!(pair[0].category() <= pair[1].category(), "{:?}", pair);
+        }
 
-        assert_eq!(compare_hands(&hand1_score, &hand2_score), Ordering::Equal);
+        assert_eq!(scores.len(), 6_188);
+        assert_eq!(scores.first().unwrap().category(), Category::HighCard);
+        assert_eq!(scores.last(), Some(&Score::FiveOfAKind(Rank::Ace)));
     }
 
     #[test]
-    fn test_sample5() {
-        let hand1 = &PokerHand::from_str("A2345").unwrap();
-        let hand1_score = hand1.score();
-        let hand2 = &PokerHand::from_str("23456").unwrap();
-        let hand2_score = hand2.score();
+    fn test_straights_run_from_the_wheel_to_the_ace() {
+        let mut straights: Vec<Score> = rank_multisets().map(score)
+            .filter(|score| score.category() == Category::Straight)
+            .collect();
+        straights.sort();
 
-        assert_eq!(hand1_score, Score::Straight(Card::Five));
-        assert_eq!(hand2_score, Score::Straight(Card::Six));
-        assert_eq!(compare_hands(&hand1_score, &hand2_score), Ordering::Greater);
+        assert_eq!(straights.len(), 10);
+        assert_eq!(straights.first(), Some(&Score::Straight(Rank::Five)));
+        assert_eq!(straights.last(), Some(&Score::Straight(Rank::Ace)));
+        assert_eq!(score([Rank::Ace, Rank::Two, Rank::Three, Rank::Four, Rank::Five]), Score::Straight(Rank::Five));
     }
 
     #[test]
-    fn test_sample6() {
-        let hand1 = &PokerHand::from_str("QQ2AT").unwrap();
-        let hand1_score = hand1.score();
-        assert_eq!(hand1_score, Score::Pair{card:Card::Queen, kickers:[Card::Two, Card::Ten, Card::Ace]});
+    fn test_kickers_break_ties_from_the_highest() {
+        // Apart from straights, hands in a category compare by their grouped ranks from the highest.
+        let hands: Vec<[Rank; 5]> = rank_multisets().filter(|ranks| score(*ranks).category() != Category::Straight).collect();
 
-        let hand2 = &PokerHand::from_str("QQT2J").unwrap();
-        let hand2_score = hand2.score();
-        assert_eq!(hand2_score, Score::Pair{card:Card::Queen, kickers:[Card::Two, Card::Ten, Card::Jack]});
-        
-        assert_eq!(compare_hands(&hand1_score, &hand2_score), Ordering::Less);
-    }
\ No newline at end of file
+        let mut by_score = hands.clone();
+        by_score.sort_by_key(|ranks| score(*ranks));
+        let mut by_ranks = hands;
+        by_ranks.sort_by_key(|ranks| (score(*ranks).category(), grouped(ranks)));
+
+        assert_eq!(by_score, by_ranks);
+    }